@@ -1,12 +1,19 @@
-use crate::configs::StarshipRootConfig;
+use crate::configs::{self, StarshipRootConfig};
+use crate::context::{Context, Shell};
+use crate::presets;
 use crate::utils;
 use ansi_term::{Color, Style};
+use once_cell::sync::OnceCell;
 
 use std::clone::Clone;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::marker::Sized;
 
 use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
 use toml::Value;
 
 /// Root config of a module.
@@ -48,6 +55,229 @@ where
     fn load_config(&self, config: &'a Value) -> Self {
         Self::from_config(config).unwrap_or_else(|| self.clone())
     }
+
+    /// Convert `self` back to a toml value, or `None` if there is nothing to write (e.g. an
+    /// unset `Option`).
+    fn to_config(&self) -> Option<Value> {
+        None
+    }
+
+    /// A JSON Schema describing the toml values accepted by `from_config`.
+    fn schema() -> serde_json::Value {
+        serde_json::json!({})
+    }
+
+    /// Check a toml value against this config, pushing the problems found to `errors`.
+    ///
+    /// `path` is the list of keys leading to `config`, used when reporting errors.
+    fn validate(config: &'a Value, path: &[String], errors: &mut Vec<ConfigError>) {
+        if Self::from_config(config).is_none() {
+            errors.push(ConfigError::invalid_value(path, config));
+        }
+    }
+}
+
+/// A problem found while validating a configuration.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfigError {
+    /// The list of keys leading to the faulty value
+    pub path: Vec<String>,
+    pub kind: ConfigErrorKind,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConfigErrorKind {
+    /// The key isn't used by the module
+    UnknownKey,
+    /// The value has the wrong type or can't be parsed
+    InvalidValue(String),
+    /// The value isn't a valid style string
+    InvalidStyle(String),
+    /// An included file doesn't exist
+    MissingInclude(PathBuf),
+    /// The selected palette isn't defined in `palettes`
+    UndefinedPalette(String),
+    /// The key was replaced by a release
+    DeprecatedKey {
+        version: &'static str,
+        replacement: &'static str,
+    },
+    /// The key of a project file is ignored, since the project isn't trusted
+    UntrustedProjectKey,
+}
+
+impl ConfigError {
+    pub fn unknown_key(path: &[String]) -> Self {
+        ConfigError {
+            path: path.to_vec(),
+            kind: ConfigErrorKind::UnknownKey,
+        }
+    }
+
+    pub fn invalid_value(path: &[String], value: &Value) -> Self {
+        ConfigError {
+            path: path.to_vec(),
+            kind: ConfigErrorKind::InvalidValue(value.to_string()),
+        }
+    }
+
+    pub fn invalid_style(path: &[String], style: &str) -> Self {
+        ConfigError {
+            path: path.to_vec(),
+            kind: ConfigErrorKind::InvalidStyle(style.to_string()),
+        }
+    }
+
+    pub fn missing_include(file_path: &Path) -> Self {
+        ConfigError {
+            path: vec![INCLUDE_KEY.to_string()],
+            kind: ConfigErrorKind::MissingInclude(file_path.into()),
+        }
+    }
+
+    pub fn untrusted_project_key(key: &str) -> Self {
+        ConfigError {
+            path: vec![key.to_string()],
+            kind: ConfigErrorKind::UntrustedProjectKey,
+        }
+    }
+
+    pub fn undefined_palette(name: &str) -> Self {
+        ConfigError {
+            path: vec![PALETTE_KEY.to_string()],
+            kind: ConfigErrorKind::UndefinedPalette(name.to_string()),
+        }
+    }
+
+    /// The path to the faulty value, with keys separated by dots
+    pub fn key(&self) -> String {
+        self.path.join(".")
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            ConfigErrorKind::UnknownKey => write!(f, "unknown key `{}`", self.key()),
+            ConfigErrorKind::InvalidValue(value) => {
+                write!(f, "invalid value {} for `{}`", value, self.key())
+            }
+            ConfigErrorKind::InvalidStyle(style) => {
+                write!(f, "invalid style string \"{}\" for `{}`", style, self.key())
+            }
+            ConfigErrorKind::MissingInclude(file_path) => {
+                write!(f, "included file {:?} not found", file_path)
+            }
+            ConfigErrorKind::UndefinedPalette(name) => {
+                write!(f, "palette `{}` is not defined in `{}`", name, PALETTES_KEY)
+            }
+            ConfigErrorKind::DeprecatedKey {
+                version,
+                replacement,
+            } => write!(
+                f,
+                "`{}` was replaced by {} in {}, run `starship config migrate`",
+                self.key(),
+                replacement,
+                version
+            ),
+            ConfigErrorKind::UntrustedProjectKey => write!(
+                f,
+                "`{}` is ignored, the project isn't in `{}`",
+                self.key(),
+                TRUSTED_PROJECTS_KEY
+            ),
+        }
+    }
+}
+
+/// Report an error if `config` is a string which can't be parsed as a style string.
+///
+/// Values which aren't strings are left to the type check of the field.
+pub fn validate_style(config: &Value, path: &[String], errors: &mut Vec<ConfigError>) {
+    if let Some(style) = config.as_str() {
+        // `none` is a valid token which resets the style
+        let is_none = style
+            .split_whitespace()
+            .any(|token| token.eq_ignore_ascii_case("none"));
+        if !is_none && parse_style_string(style, None).is_none() {
+            errors.push(ConfigError::invalid_style(path, style));
+        }
+    }
+}
+
+/// Find the line (starting at 1) where the value at `path` is defined in `toml_content`.
+///
+/// Values nested in inline tables or arrays are reported at the line of their closest
+/// parent defined on its own line.
+pub fn find_line(toml_content: &str, path: &[String]) -> Option<usize> {
+    fn split_key(key: &str) -> Vec<String> {
+        key.split('.')
+            .map(|part| {
+                part.trim()
+                    .trim_matches(|c| c == '"' || c == '\'')
+                    .to_string()
+            })
+            .collect()
+    }
+
+    // Tables nested in an array of tables belong to its last element, e.g. `[when.character]`
+    // after a `[[when]]` header
+    fn index_arrays(name: &[String], array_counts: &HashMap<Vec<String>, usize>) -> Vec<String> {
+        let mut path = Vec::new();
+        for (depth, part) in name.iter().enumerate() {
+            path.push(part.clone());
+            if let Some(count) = array_counts.get(&name[..=depth]) {
+                path.push((count - 1).to_string());
+            }
+        }
+        path
+    }
+
+    let mut table: Vec<String> = Vec::new();
+    let mut array_counts: HashMap<Vec<String>, usize> = HashMap::new();
+    let mut best_match: Option<(usize, usize)> = None;
+
+    for (index, line) in toml_content.lines().enumerate() {
+        let line = line.trim();
+        let line_path = if line.starts_with("[[") {
+            let name = split_key(line.trim_start_matches('[').split(']').next()?);
+            let (last, parents) = name.split_last()?;
+            table = index_arrays(parents, &array_counts);
+            table.push(last.clone());
+            let count = array_counts.entry(name).or_insert(0);
+            table.push(count.to_string());
+            *count += 1;
+            table.clone()
+        } else if line.starts_with('[') {
+            let name = split_key(line.trim_start_matches('[').split(']').next()?);
+            table = index_arrays(&name, &array_counts);
+            table.clone()
+        } else if let Some(equal) = line.find('=') {
+            let mut line_path = table.clone();
+            line_path.extend(split_key(&line[..equal]));
+            line_path
+        } else {
+            continue;
+        };
+
+        let matching = line_path
+            .iter()
+            .zip(path)
+            .take_while(|(a, b)| a == b)
+            .count();
+        if matching == path.len() && matching == line_path.len() {
+            return Some(index + 1);
+        }
+        if matching == line_path.len() {
+            match best_match {
+                Some((len, _)) if len >= matching => {}
+                _ => best_match = Some((matching, index + 1)),
+            }
+        }
+    }
+
+    best_match.map(|(_, line)| line)
 }
 
 // TODO: Add logging to default implementations
@@ -55,11 +285,23 @@ impl<'a> ModuleConfig<'a> for &'a str {
     fn from_config(config: &'a Value) -> Option<Self> {
         config.as_str()
     }
+
+    fn to_config(&self) -> Option<Value> {
+        Some(Value::from(*self))
+    }
+
+    fn schema() -> serde_json::Value {
+        serde_json::json!({ "type": "string" })
+    }
 }
 
 impl<'a> ModuleConfig<'a> for Style {
     fn from_config(config: &Value) -> Option<Self> {
-        parse_style_string(config.as_str()?)
+        parse_style_string(config.as_str()?, None)
+    }
+
+    fn schema() -> serde_json::Value {
+        serde_json::json!({ "type": "string" })
     }
 }
 
@@ -67,12 +309,28 @@ impl<'a> ModuleConfig<'a> for bool {
     fn from_config(config: &Value) -> Option<Self> {
         config.as_bool()
     }
+
+    fn to_config(&self) -> Option<Value> {
+        Some(Value::from(*self))
+    }
+
+    fn schema() -> serde_json::Value {
+        serde_json::json!({ "type": "boolean" })
+    }
 }
 
 impl<'a> ModuleConfig<'a> for i64 {
     fn from_config(config: &Value) -> Option<Self> {
         config.as_integer()
     }
+
+    fn to_config(&self) -> Option<Value> {
+        Some(Value::from(*self))
+    }
+
+    fn schema() -> serde_json::Value {
+        serde_json::json!({ "type": "integer" })
+    }
 }
 
 impl<'a> ModuleConfig<'a> for u64 {
@@ -90,12 +348,28 @@ impl<'a> ModuleConfig<'a> for u64 {
             _ => None,
         }
     }
+
+    fn to_config(&self) -> Option<Value> {
+        Some(Value::from(*self as i64))
+    }
+
+    fn schema() -> serde_json::Value {
+        serde_json::json!({ "type": "integer", "minimum": 1 })
+    }
 }
 
 impl<'a> ModuleConfig<'a> for f64 {
     fn from_config(config: &Value) -> Option<Self> {
         config.as_float()
     }
+
+    fn to_config(&self) -> Option<Value> {
+        Some(Value::from(*self))
+    }
+
+    fn schema() -> serde_json::Value {
+        serde_json::json!({ "type": "number" })
+    }
 }
 
 impl<'a> ModuleConfig<'a> for usize {
@@ -112,6 +386,14 @@ impl<'a> ModuleConfig<'a> for usize {
             _ => None,
         }
     }
+
+    fn to_config(&self) -> Option<Value> {
+        Some(Value::from(*self as i64))
+    }
+
+    fn schema() -> serde_json::Value {
+        serde_json::json!({ "type": "integer", "minimum": 1 })
+    }
 }
 
 impl<'a, T> ModuleConfig<'a> for Vec<T>
@@ -125,6 +407,34 @@ where
             .map(|value| T::from_config(value))
             .collect()
     }
+
+    fn to_config(&self) -> Option<Value> {
+        Some(Value::Array(
+            self.iter().filter_map(ModuleConfig::to_config).collect(),
+        ))
+    }
+
+    fn schema() -> serde_json::Value {
+        serde_json::json!({ "type": "array", "items": T::schema() })
+    }
+
+    fn validate(config: &'a Value, path: &[String], errors: &mut Vec<ConfigError>) {
+        match config.as_array() {
+            Some(array) => validate_array::<T>(array, path, errors),
+            None => errors.push(ConfigError::invalid_value(path, config)),
+        }
+    }
+}
+
+fn validate_array<'a, T>(array: &'a [Value], path: &[String], errors: &mut Vec<ConfigError>)
+where
+    T: ModuleConfig<'a>,
+{
+    for (index, value) in array.iter().enumerate() {
+        let mut item_path = path.to_vec();
+        item_path.push(index.to_string());
+        T::validate(value, &item_path, errors);
+    }
 }
 
 impl<'a, T, S: ::std::hash::BuildHasher + Default> ModuleConfig<'a> for HashMap<String, T, S>
@@ -141,6 +451,33 @@ where
 
         Some(hm)
     }
+
+    fn to_config(&self) -> Option<Value> {
+        let mut table = toml::value::Table::new();
+        for (key, value) in self.iter() {
+            if let Some(value) = value.to_config() {
+                table.insert(key.clone(), value);
+            }
+        }
+        Some(Value::Table(table))
+    }
+
+    fn schema() -> serde_json::Value {
+        serde_json::json!({ "type": "object", "additionalProperties": T::schema() })
+    }
+
+    fn validate(config: &'a Value, path: &[String], errors: &mut Vec<ConfigError>) {
+        match config.as_table() {
+            Some(table) => {
+                for (key, value) in table.iter() {
+                    let mut item_path = path.to_vec();
+                    item_path.push(key.clone());
+                    T::validate(value, &item_path, errors);
+                }
+            }
+            None => errors.push(ConfigError::invalid_value(path, config)),
+        }
+    }
 }
 
 impl<'a, T> ModuleConfig<'a> for Option<T>
@@ -150,6 +487,18 @@ where
     fn from_config(config: &'a Value) -> Option<Self> {
         Some(T::from_config(config))
     }
+
+    fn to_config(&self) -> Option<Value> {
+        self.as_ref()?.to_config()
+    }
+
+    fn schema() -> serde_json::Value {
+        T::schema()
+    }
+
+    fn validate(config: &'a Value, path: &[String], errors: &mut Vec<ConfigError>) {
+        T::validate(config, path, errors)
+    }
 }
 
 /// A wrapper around `Vec<T>` that implements `ModuleConfig`, and either
@@ -174,6 +523,237 @@ where
 
         Some(VecOr(vec))
     }
+
+    fn to_config(&self) -> Option<Value> {
+        match self.0.as_slice() {
+            [item] => item.to_config(),
+            items => Some(Value::Array(
+                items.iter().filter_map(ModuleConfig::to_config).collect(),
+            )),
+        }
+    }
+
+    fn schema() -> serde_json::Value {
+        serde_json::json!({
+            "anyOf": [T::schema(), { "type": "array", "items": T::schema() }]
+        })
+    }
+
+    fn validate(config: &'a Value, path: &[String], errors: &mut Vec<ConfigError>) {
+        match config.as_array() {
+            Some(array) if T::from_config(config).is_none() => {
+                validate_array::<T>(array, path, errors)
+            }
+            _ => T::validate(config, path, errors),
+        }
+    }
+}
+
+/// Name of the configuration file looked up in the current directory and its parents
+const PROJECT_CONFIG_FILE: &str = ".starship.toml";
+
+/// Prefix of the environment variables overriding config values, e.g.
+/// `STARSHIP__git_branch__style` for the `style` option of the `git_branch` module
+const ENV_OVERRIDE_PREFIX: &str = "STARSHIP__";
+
+/// Where a layer of the configuration comes from
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConfigSource {
+    /// The module symbols selected by the root `symbols` option
+    Symbols(SymbolSet),
+    /// The bundled preset selected by the root `preset` option
+    Preset(&'static str),
+    /// The system-wide configuration file
+    System(PathBuf),
+    /// The user configuration file
+    User(PathBuf),
+    /// A `.starship.toml` file in the current directory or one of its parents
+    Project(PathBuf),
+    /// The overrides of the selected theme, from the `[theme.light]` or `[theme.dark]` table
+    Theme(Theme),
+    /// The `STARSHIP__module__key` environment variables
+    Environment,
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigSource::Symbols(_) => write!(f, "symbols"),
+            ConfigSource::Preset(_) => write!(f, "preset"),
+            ConfigSource::System(_) => write!(f, "system"),
+            ConfigSource::User(_) => write!(f, "user"),
+            ConfigSource::Project(_) => write!(f, "project"),
+            ConfigSource::Theme(_) => write!(f, "theme"),
+            ConfigSource::Environment => write!(f, "env"),
+        }
+    }
+}
+
+/// A single layer of the configuration
+pub struct ConfigLayer {
+    pub source: ConfigSource,
+    pub config: Value,
+}
+
+/// Deep-merge `other` into `base`: tables are merged key by key, other values are replaced
+pub fn merge_config(base: &mut Value, other: Value) {
+    match (base, other) {
+        (Value::Table(base), Value::Table(other)) => {
+            for (key, value) in other {
+                match base.get_mut(&key) {
+                    Some(base_value) => merge_config(base_value, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, other) => *base = other,
+    }
+}
+
+/// Build a config table from the `STARSHIP__module__key=value` environment variables
+///
+/// Values are parsed as TOML values when possible (e.g. `true` or `3`), and are used as
+/// strings otherwise.
+fn config_from_env(vars: impl Iterator<Item = (OsString, OsString)>) -> Option<Value> {
+    let mut config = Value::Table(toml::value::Table::new());
+    let mut found = false;
+
+    for (name, raw_value) in vars {
+        // Other variables may hold anything, only the overrides have to be valid UTF-8
+        let name = match name.to_str() {
+            Some(name) if name.starts_with(ENV_OVERRIDE_PREFIX) => name.to_string(),
+            _ => continue,
+        };
+        let raw_value = match raw_value.into_string() {
+            Ok(raw_value) => raw_value,
+            Err(_) => {
+                log::warn!(
+                    "Ignoring config override `{}`: its value isn't valid UTF-8",
+                    name
+                );
+                continue;
+            }
+        };
+        let keys: Vec<&str> = name[ENV_OVERRIDE_PREFIX.len()..].split("__").collect();
+        if keys.iter().any(|key| key.is_empty()) {
+            log::warn!("Ignoring invalid config override `{}`", name);
+            continue;
+        }
+
+        let mut value = toml::from_str::<toml::value::Table>(&format!("value = {}", raw_value))
+            .ok()
+            .and_then(|mut table| table.remove("value"))
+            .unwrap_or_else(|| Value::String(raw_value.clone()));
+        for key in keys.iter().rev() {
+            let mut table = toml::value::Table::new();
+            table.insert(key.to_string(), value);
+            value = Value::Table(table);
+        }
+
+        log::debug!("Config override from {}: {}", name, raw_value);
+        merge_config(&mut config, value);
+        found = true;
+    }
+
+    if found {
+        Some(config)
+    } else {
+        None
+    }
+}
+
+/// Top-level key listing the files to load before a configuration file
+const INCLUDE_KEY: &str = "include";
+
+/// Top-level key listing the project directories whose `.starship.toml` is trusted
+pub const TRUSTED_PROJECTS_KEY: &str = "trusted_projects";
+
+/// The top-level keys of a project file which are only used when the project is trusted:
+/// custom modules run commands, and included files can be anywhere on the disk
+pub const TRUSTED_PROJECT_KEYS: &[&str] = &[INCLUDE_KEY, "custom"];
+
+/// Top-level array of tables holding overrides applied on the hosts, shells and
+/// environments matching their conditions
+pub const WHEN_KEY: &str = "when";
+
+/// The keys of a `[[when]]` block which are conditions, its other keys being overrides
+pub const WHEN_CONDITIONS: &[&str] = &["hostname", "os", "shell", "env"];
+
+/// Whether a key of a `[[when]]` block is a condition
+///
+/// Conditions are patterns, so a table under `hostname` configures the module of that name.
+pub fn is_when_condition(key: &str, value: &Value) -> bool {
+    match key {
+        "env" => true,
+        key => WHEN_CONDITIONS.contains(&key) && !value.is_table(),
+    }
+}
+
+/// Top-level key selecting the set of symbols used by the modules
+pub const SYMBOLS_KEY: &str = "symbols";
+
+/// Top-level key selecting a bundled preset as the base of the configuration
+pub const PRESET_KEY: &str = "preset";
+
+/// Top-level table holding the overrides of each theme and the `mode` selecting one of them
+pub const THEME_KEY: &str = "theme";
+
+/// Top-level key selecting the palette used to resolve color names
+const PALETTE_KEY: &str = "palette";
+
+/// Top-level table of the palettes, each mapping color names to colors
+const PALETTES_KEY: &str = "palettes";
+
+/// The files listed in the `include` key of a configuration file, in order
+///
+/// `~` is expanded to the home directory, and relative paths are resolved from the directory
+/// of the including file.
+pub fn include_paths(config: &Value, file_path: &Path) -> Vec<PathBuf> {
+    let includes = match config.get(INCLUDE_KEY) {
+        Some(Value::String(include)) => vec![include.as_str()],
+        Some(Value::Array(includes)) => includes.iter().filter_map(Value::as_str).collect(),
+        _ => return Vec::new(),
+    };
+    let base_dir = file_path.parent().unwrap_or_else(|| Path::new(""));
+
+    includes
+        .into_iter()
+        .map(|include| base_dir.join(expand_home(include)))
+        .collect()
+}
+
+/// Expand a leading `~/` of a path to the home directory
+fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(relative) => dirs_next::home_dir()
+            .map(|home_dir| home_dir.join(relative))
+            .unwrap_or_else(|| PathBuf::from(path)),
+        None => PathBuf::from(path),
+    }
+}
+
+/// Whether the directory of a project file is one of the `trusted_projects` of `config`, or
+/// is inside one of them
+pub fn is_trusted_project(project_file: &Path, config: &Value) -> bool {
+    let project_dir = match project_file.parent() {
+        Some(project_dir) => project_dir,
+        None => return false,
+    };
+    let project_dir = fs::canonicalize(project_dir).unwrap_or_else(|_| project_dir.into());
+
+    config
+        .get(TRUSTED_PROJECTS_KEY)
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .map(expand_home)
+        .any(|trusted_dir| {
+            let trusted_dir = fs::canonicalize(&trusted_dir).unwrap_or(trusted_dir);
+            project_dir.starts_with(trusted_dir)
+        })
 }
 
 /// Root config of starship.
@@ -182,41 +762,251 @@ pub struct StarshipConfig {
 }
 
 impl StarshipConfig {
-    /// Initialize the Config struct
-    pub fn initialize() -> Self {
-        if let Some(file_data) = Self::config_from_file() {
-            StarshipConfig {
-                config: Some(file_data),
+    /// Initialize the Config struct from every layer, including the project configuration
+    /// found by walking up from `current_dir`
+    pub fn initialize(current_dir: &Path) -> Self {
+        let config = Self::from_layers(&Self::layers(Some(current_dir)));
+        // Warn about an undefined palette once, rather than for every style using it
+        if let Some(config) = &config.config {
+            let name = config.get(PALETTE_KEY).and_then(Value::as_str);
+            if let (Some(name), None) = (name, get_palette(config)) {
+                log::warn!("Palette `{}` is not defined in `{}`", name, PALETTES_KEY);
             }
-        } else {
-            StarshipConfig {
-                config: Some(Value::Table(toml::value::Table::new())),
+        }
+        config
+    }
+
+    /// Merge the layers of the configuration, the last layer having the highest precedence
+    pub fn from_layers(layers: &[ConfigLayer]) -> Self {
+        StarshipConfig {
+            config: Some(merge_layers(layers.iter())),
+        }
+    }
+
+    /// Load every layer of the configuration, from the lowest to the highest precedence:
+    /// the selected symbols, the selected preset, the system file, the user file, the project file, the overrides
+    /// of the selected theme and the environment overrides
+    pub fn layers(current_dir: Option<&Path>) -> Vec<ConfigLayer> {
+        let when_context = WhenContext::default();
+        let env_layer = config_from_env(env::vars_os()).map(|config| ConfigLayer {
+            source: ConfigSource::Environment,
+            config,
+        });
+
+        let mut layers: Vec<ConfigLayer> = Vec::new();
+        for source in Self::file_sources(current_dir) {
+            let config = match &source {
+                ConfigSource::System(path) | ConfigSource::User(path) => {
+                    Self::config_from_file(path, &when_context)
+                }
+                // A project can't run commands before the user trusts it
+                ConfigSource::Project(path) => {
+                    let trusted_config = merge_layers(layers.iter().chain(env_layer.iter()));
+                    if is_trusted_project(path, &trusted_config) {
+                        Self::config_from_file(path, &when_context)
+                    } else {
+                        Self::untrusted_config_from_file(path, &when_context)
+                    }
+                    .map(|mut config| {
+                        if let Some(table) = config.as_table_mut() {
+                            table.remove(TRUSTED_PROJECTS_KEY);
+                        }
+                        config
+                    })
+                }
+                ConfigSource::Symbols(_)
+                | ConfigSource::Preset(_)
+                | ConfigSource::Theme(_)
+                | ConfigSource::Environment => None,
+            };
+            if let Some(config) = config {
+                layers.push(ConfigLayer { source, config });
+            }
+        }
+
+        // Replaced options are reported once per key at the error level, which is shown without
+        // setting STARSHIP_LOG
+        let mut deprecated_keys = HashSet::new();
+        for layer in &layers {
+            for deprecation in configs::migrations::find_deprecated(&layer.config) {
+                if !deprecated_keys.insert(deprecation.key()) {
+                    continue;
+                }
+                log::error!(
+                    "{} config: `{}` was replaced by {} in {}, run `starship config migrate`",
+                    layer.source,
+                    deprecation.key(),
+                    deprecation.migration.replacement,
+                    deprecation.migration.version
+                );
+            }
+        }
+
+        // The preset, the symbols and the theme can be selected by any layer, including the
+        // environment overrides and the preset itself
+        let mut merged = merge_layers(layers.iter().chain(env_layer.iter()));
+        if let Some(layer) = preset_layer(&merged) {
+            layers.insert(0, layer);
+            merged = merge_layers(layers.iter().chain(env_layer.iter()));
+        }
+        if let Some(layer) = symbols_layer(&merged) {
+            layers.insert(0, layer);
+            merged = merge_layers(layers.iter().chain(env_layer.iter()));
+        }
+
+        let theme = Theme::select(
+            &merged,
+            env::var("STARSHIP_THEME").ok().as_deref(),
+            env::var("COLORFGBG").ok().as_deref(),
+        );
+        if let Some(theme) = theme {
+            log::debug!("Using the {} theme", theme.name());
+            if let Some(overrides) = get_theme_overrides(&merged, theme) {
+                layers.push(ConfigLayer {
+                    source: ConfigSource::Theme(theme),
+                    config: overrides.clone(),
+                });
             }
         }
+
+        layers.extend(env_layer);
+        layers
+    }
+
+    /// The sources of the file layers, from the lowest to the highest precedence, whether
+    /// their file exists or not
+    pub fn file_sources(current_dir: Option<&Path>) -> Vec<ConfigSource> {
+        let mut sources = Vec::new();
+        if let Some(path) = Self::system_config_path() {
+            sources.push(ConfigSource::System(path));
+        }
+        if let Some(path) = Self::user_config_path() {
+            sources.push(ConfigSource::User(path));
+        }
+        if let Some(path) = current_dir.and_then(Self::project_config_path) {
+            sources.push(ConfigSource::Project(path));
+        }
+        sources
+    }
+
+    /// The path of the system-wide configuration file
+    fn system_config_path() -> Option<PathBuf> {
+        if let Some(path) = env::var_os("STARSHIP_SYSTEM_CONFIG") {
+            log::debug!("STARSHIP_SYSTEM_CONFIG is set: \n{:?}", &path);
+            return Some(PathBuf::from(path));
+        }
+
+        if cfg!(windows) {
+            env::var_os("ProgramData")
+                .map(|program_data| PathBuf::from(program_data).join("starship/starship.toml"))
+        } else {
+            Some(PathBuf::from("/etc/starship.toml"))
+        }
     }
 
-    /// Create a config from a starship configuration file
-    fn config_from_file() -> Option<Value> {
-        let file_path = if let Ok(path) = env::var("STARSHIP_CONFIG") {
+    /// The path of the user configuration file
+    pub fn user_config_path() -> Option<PathBuf> {
+        if let Ok(path) = env::var("STARSHIP_CONFIG") {
             // Use $STARSHIP_CONFIG as the config path if available
             log::debug!("STARSHIP_CONFIG is set: \n{}", &path);
-            path
+            Some(PathBuf::from(path))
         } else {
             // Default to using ~/.config/starship.toml
             log::debug!("STARSHIP_CONFIG is not set");
             let config_path = dirs_next::home_dir()?.join(".config/starship.toml");
-            let config_path_str = config_path.to_str()?.to_owned();
-            log::debug!("Using default config path: {}", config_path_str);
-            config_path_str
-        };
+            log::debug!("Using default config path: {:?}", config_path);
+            Some(config_path)
+        }
+    }
+
+    /// Find the closest `.starship.toml` file in `current_dir` or one of its parents
+    fn project_config_path(current_dir: &Path) -> Option<PathBuf> {
+        current_dir
+            .ancestors()
+            .map(|dir| dir.join(PROJECT_CONFIG_FILE))
+            .find(|path| path.is_file())
+    }
+
+    /// Create a config from a starship configuration file and the files it includes
+    fn config_from_file(file_path: &Path, when_context: &WhenContext) -> Option<Value> {
+        Self::load_config_file(file_path, &mut Vec::new(), when_context)
+    }
 
-        let toml_content = match utils::read_file(&file_path) {
+    /// Create a config from a project file which isn't trusted, ignoring its includes and its
+    /// custom modules
+    fn untrusted_config_from_file(file_path: &Path, when_context: &WhenContext) -> Option<Value> {
+        let mut parsed = Self::parse_config_file(file_path)?;
+        apply_when_blocks(&mut parsed, when_context);
+        if let Some(table) = parsed.as_table_mut() {
+            for key in TRUSTED_PROJECT_KEYS {
+                if table.remove(*key).is_some() {
+                    log::warn!(
+                        "Ignoring `{}` in {:?}: add the project to `{}` to trust it",
+                        key,
+                        file_path,
+                        TRUSTED_PROJECTS_KEY
+                    );
+                }
+            }
+        }
+        Some(parsed)
+    }
+
+    /// Load a configuration file, applying its matching `[[when]]` blocks and merging it over
+    /// the files listed in its `include` key
+    ///
+    /// `loading` holds the files currently being loaded, to detect include cycles.
+    fn load_config_file(
+        file_path: &Path,
+        loading: &mut Vec<PathBuf>,
+        when_context: &WhenContext,
+    ) -> Option<Value> {
+        let canonical_path = fs::canonicalize(file_path).unwrap_or_else(|_| file_path.into());
+        if loading.contains(&canonical_path) {
+            log::error!(
+                "Unable to include the config file {:?}: it includes itself",
+                file_path
+            );
+            return None;
+        }
+
+        let mut parsed = Self::parse_config_file(file_path)?;
+        apply_when_blocks(&mut parsed, when_context);
+        let includes = include_paths(&parsed, file_path);
+        if includes.is_empty() {
+            return Some(parsed);
+        }
+
+        loading.push(canonical_path);
+        let mut config = Value::Table(toml::value::Table::new());
+        for include in includes {
+            match Self::load_config_file(&include, loading, when_context) {
+                Some(included) => merge_config(&mut config, included),
+                None => log::error!(
+                    "Unable to load the config file {:?} included from {:?}",
+                    include,
+                    file_path
+                ),
+            }
+        }
+        loading.pop();
+
+        if let Some(table) = parsed.as_table_mut() {
+            table.remove(INCLUDE_KEY);
+        }
+        merge_config(&mut config, parsed);
+        Some(config)
+    }
+
+    /// Parse a single configuration file, without resolving its includes
+    fn parse_config_file(file_path: &Path) -> Option<Value> {
+        let toml_content = match utils::read_file(file_path) {
             Ok(content) => {
                 log::trace!("Config file content: \n{}", &content);
                 Some(content)
             }
             Err(e) => {
-                log::debug!("Unable to read config file content: \n{}", &e);
+                log::debug!("Unable to read config file {:?}: \n{}", file_path, &e);
                 None
             }
         }?;
@@ -227,7 +1017,7 @@ impl StarshipConfig {
                 Some(parsed)
             }
             Err(error) => {
-                log::debug!("Unable to parse the config file: {}", error);
+                log::error!("Unable to parse the config file {:?}: {}", file_path, error);
                 None
             }
         }
@@ -268,6 +1058,11 @@ impl StarshipConfig {
         self.config.as_ref()?.as_table()?.get("custom")?.as_table()
     }
 
+    /// Get the colors of the palette selected by the root `palette` option, if any
+    pub fn get_palette(&self) -> Option<&toml::value::Table> {
+        get_palette(self.config.as_ref()?)
+    }
+
     pub fn get_root_config(&self) -> StarshipRootConfig {
         if let Some(root_config) = &self.config {
             StarshipRootConfig::load(root_config)
@@ -277,73 +1072,13 @@ impl StarshipConfig {
     }
 }
 
-#[derive(Clone)]
-pub struct SegmentConfig<'a> {
-    pub value: &'a str,
-    pub style: Option<Style>,
-}
-
-impl<'a> ModuleConfig<'a> for SegmentConfig<'a> {
-    fn from_config(config: &'a Value) -> Option<Self> {
-        match config {
-            Value::String(ref config_str) => Some(Self {
-                value: config_str,
-                style: None,
-            }),
-            Value::Table(ref config_table) => Some(Self {
-                value: config_table.get("value")?.as_str()?,
-                style: config_table.get("style").and_then(<Style>::from_config),
-            }),
-            _ => None,
-        }
-    }
-
-    fn load_config(&self, config: &'a Value) -> Self {
-        let mut new_config = self.clone();
-        match config {
-            Value::String(ref config_str) => {
-                new_config.value = config_str;
-            }
-            Value::Table(ref config_table) => {
-                if let Some(Value::String(value)) = config_table.get("value") {
-                    new_config.value = value;
-                };
-                if let Some(style) = config_table.get("style") {
-                    new_config.style = <Style>::from_config(style);
-                };
-            }
-            _ => {}
-        };
-        new_config
-    }
-}
-
-impl<'a> SegmentConfig<'a> {
-    pub fn new(value: &'a str) -> Self {
-        Self { value, style: None }
-    }
-
-    /// Immutably set value
-    pub fn with_value(&self, value: &'a str) -> Self {
-        Self {
-            value,
-            style: self.style,
-        }
-    }
-
-    /// Immutably set style
-    pub fn with_style(&self, style: Option<Style>) -> Self {
-        Self {
-            value: self.value,
-            style,
-        }
-    }
-}
-
-impl Default for SegmentConfig<'static> {
-    fn default() -> Self {
-        Self::new("")
-    }
+/// Get the colors of the palette selected by the `palette` option of a root config
+pub fn get_palette(config: &Value) -> Option<&toml::value::Table> {
+    let name = config.get(PALETTE_KEY)?.as_str()?;
+    config
+        .get(PALETTES_KEY)
+        .and_then(|palettes| palettes.get(name))
+        .and_then(Value::as_table)
 }
 
 /** Parse a style string which represents an ansi style. Valid tokens in the style
@@ -354,8 +1089,38 @@ impl Default for SegmentConfig<'static> {
  - 'bold'
  - 'italic'
  - '<color>'        (see the parse_color_string doc for valid color strings)
+ - 'prev_fg'       (the foreground color of the previous segment)
+ - 'prev_bg'       (the background color of the previous segment)
+
+ Color names are looked up in the palette selected in the config of `context` first.
 */
-pub fn parse_style_string(style_string: &str) -> Option<ansi_term::Style> {
+pub fn parse_style_string(style_string: &str, context: Option<&Context>) -> Option<Style> {
+    parse_style_string_with_prev(style_string, None, context)
+}
+
+/// Parse a style string where `prev_fg` and `prev_bg` refer to the colors of `prev_style`
+pub fn parse_style_string_with_prev(
+    style_string: &str,
+    prev_style: Option<Style>,
+    context: Option<&Context>,
+) -> Option<Style> {
+    let palette = context.and_then(|context| context.config.get_palette());
+    parse_style(style_string, prev_style, palette)
+}
+
+/// Parse a style string, looking up color names in `palette` first
+pub fn parse_style_string_with_palette(
+    style_string: &str,
+    palette: Option<&toml::value::Table>,
+) -> Option<Style> {
+    parse_style(style_string, None, palette)
+}
+
+fn parse_style(
+    style_string: &str,
+    prev_style: Option<Style>,
+    palette: Option<&toml::value::Table>,
+) -> Option<Style> {
     style_string
         .split_whitespace()
         .fold(Some(ansi_term::Style::new()), |maybe_style, token| {
@@ -379,8 +1144,30 @@ pub fn parse_style_string(style_string: &str) -> Option<ansi_term::Style> {
                     "dimmed" => Some(style.dimmed()),
                     "none" => None,
 
+                    // Copy a color of the previous segment, unsetting the color if it has none
+                    "prev_fg" | "prev_bg" => {
+                        let prev_color = prev_style.and_then(|prev_style| {
+                            if token == "prev_fg" {
+                                prev_style.foreground
+                            } else {
+                                prev_style.background
+                            }
+                        });
+                        if col_fg {
+                            Some(Style {
+                                foreground: prev_color,
+                                ..style
+                            })
+                        } else {
+                            Some(Style {
+                                background: prev_color,
+                                ..style
+                            })
+                        }
+                    }
+
                     // Try to see if this token parses as a valid color string
-                    color_string => parse_color_string(color_string).map(|ansi_color| {
+                    color_string => parse_palette_color(color_string, palette).map(|ansi_color| {
                         if col_fg {
                             style.fg(ansi_color)
                         } else {
@@ -392,6 +1179,38 @@ pub fn parse_style_string(style_string: &str) -> Option<ansi_term::Style> {
         })
 }
 
+/// Parse a color string, resolving names defined in `palette` to their color first
+///
+/// The colors of a palette can't refer to other names of the palette.
+fn parse_palette_color(
+    color_string: &str,
+    palette: Option<&toml::value::Table>,
+) -> Option<ansi_term::Color> {
+    let palette_color = palette.and_then(|palette| {
+        palette
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(color_string))
+            .and_then(|(_, color)| color.as_str())
+    });
+    if let Some(palette_color) = palette_color {
+        log::trace!("Read palette color {}: {}", color_string, palette_color);
+        return parse_color_string(palette_color);
+    }
+
+    let color = parse_color_string(color_string);
+    if color.is_none() {
+        if palette.is_some() {
+            log::warn!(
+                "Color `{}` is neither a valid color nor defined in the palette",
+                color_string
+            );
+        } else {
+            log::warn!("Color `{}` is not a valid color", color_string);
+        }
+    }
+    color
+}
+
 /** Parse a string that represents a color setting, returning None if this fails
  There are three valid color formats:
   - #RRGGBB      (a hash followed by an RGB hex)
@@ -406,47 +1225,508 @@ fn parse_color_string(color_string: &str) -> Option<ansi_term::Color> {
             "Attempting to read hexadecimal color string: {}",
             color_string
         );
-        let r: u8 = u8::from_str_radix(&color_string[1..3], 16).ok()?;
-        let g: u8 = u8::from_str_radix(&color_string[3..5], 16).ok()?;
-        let b: u8 = u8::from_str_radix(&color_string[5..7], 16).ok()?;
+        let r: u8 = u8::from_str_radix(color_string.get(1..3)?, 16).ok()?;
+        let g: u8 = u8::from_str_radix(color_string.get(3..5)?, 16).ok()?;
+        let b: u8 = u8::from_str_radix(color_string.get(5..7)?, 16).ok()?;
         log::trace!("Read RGB color string: {},{},{}", r, g, b);
         return Some(Color::RGB(r, g, b));
     }
 
-    // Parse a u8 (ansi color)
-    if let Result::Ok(ansi_color_num) = color_string.parse::<u8>() {
-        log::trace!("Read ANSI color string: {}", ansi_color_num);
-        return Some(Color::Fixed(ansi_color_num));
+    // Parse a u8 (ansi color)
+    if let Result::Ok(ansi_color_num) = color_string.parse::<u8>() {
+        log::trace!("Read ANSI color string: {}", ansi_color_num);
+        return Some(Color::Fixed(ansi_color_num));
+    }
+
+    // Check for any predefined color strings
+    // There are no predefined enums for bright colors, so we use Color::Fixed
+    let predefined_color = match color_string.to_lowercase().as_str() {
+        "black" => Some(Color::Black),
+        "red" => Some(Color::Red),
+        "green" => Some(Color::Green),
+        "yellow" => Some(Color::Yellow),
+        "blue" => Some(Color::Blue),
+        "purple" => Some(Color::Purple),
+        "cyan" => Some(Color::Cyan),
+        "white" => Some(Color::White),
+        "bright-black" => Some(Color::Fixed(8)), // "bright-black" is dark grey
+        "bright-red" => Some(Color::Fixed(9)),
+        "bright-green" => Some(Color::Fixed(10)),
+        "bright-yellow" => Some(Color::Fixed(11)),
+        "bright-blue" => Some(Color::Fixed(12)),
+        "bright-purple" => Some(Color::Fixed(13)),
+        "bright-cyan" => Some(Color::Fixed(14)),
+        "bright-white" => Some(Color::Fixed(15)),
+        _ => None,
+    };
+
+    if predefined_color.is_some() {
+        log::trace!("Read predefined color: {}", color_string);
+    }
+    predefined_color
+}
+
+/// Merge the config of `layers`, each one taking precedence over the previous ones
+fn merge_layers<'a>(layers: impl Iterator<Item = &'a ConfigLayer>) -> Value {
+    let mut config = Value::Table(toml::value::Table::new());
+    for layer in layers {
+        merge_config(&mut config, layer.config.clone());
+    }
+    config
+}
+
+/// Load the bundled preset selected by the `preset` option of `config`, if any
+fn preset_layer(config: &Value) -> Option<ConfigLayer> {
+    let name = config.get(PRESET_KEY)?.as_str()?;
+    let preset = match presets::get_preset(name) {
+        Some(preset) => preset,
+        None => {
+            log::error!("Unknown preset `{}`", name);
+            return None;
+        }
+    };
+
+    match toml::from_str(preset.config) {
+        Ok(config) => Some(ConfigLayer {
+            source: ConfigSource::Preset(preset.name),
+            config,
+        }),
+        Err(error) => {
+            log::error!("Unable to parse the `{}` preset: {}", preset.name, error);
+            None
+        }
+    }
+}
+
+/// The characters used by the default symbols of the modules
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SymbolSet {
+    /// Emoji and other unicode symbols, the defaults of each module
+    Unicode,
+    /// Plain text, for terminals which can't display emoji or special glyphs
+    Ascii,
+    /// Glyphs from Nerd Fonts
+    NerdFont,
+}
+
+impl<'a> ModuleConfig<'a> for SymbolSet {
+    fn from_config(config: &'a Value) -> Option<Self> {
+        match config.as_str()? {
+            "unicode" => Some(SymbolSet::Unicode),
+            "ascii" => Some(SymbolSet::Ascii),
+            "nerd-font" => Some(SymbolSet::NerdFont),
+            _ => None,
+        }
+    }
+
+    fn to_config(&self) -> Option<Value> {
+        Some(Value::from(self.name()))
+    }
+
+    fn schema() -> serde_json::Value {
+        serde_json::json!({ "type": "string", "enum": ["unicode", "ascii", "nerd-font"] })
+    }
+}
+
+impl SymbolSet {
+    pub fn name(self) -> &'static str {
+        match self {
+            SymbolSet::Unicode => "unicode",
+            SymbolSet::Ascii => "ascii",
+            SymbolSet::NerdFont => "nerd-font",
+        }
+    }
+
+    /// The bundled preset holding the symbols of this set, `None` for the defaults
+    fn preset(self) -> Option<&'static presets::Preset> {
+        match self {
+            SymbolSet::Unicode => None,
+            SymbolSet::Ascii => presets::get_preset("plain-text-symbols"),
+            SymbolSet::NerdFont => presets::get_preset("nerd-font-symbols"),
+        }
+    }
+}
+
+/// Load the symbols selected by the `symbols` option of `config`, replacing the defaults
+/// of the modules
+fn symbols_layer(config: &Value) -> Option<ConfigLayer> {
+    let symbols = SymbolSet::from_config(config.get(SYMBOLS_KEY)?)?;
+    let preset = symbols.preset()?;
+    match toml::from_str(preset.config) {
+        Ok(config) => Some(ConfigLayer {
+            source: ConfigSource::Symbols(symbols),
+            config,
+        }),
+        Err(error) => {
+            log::error!("Unable to parse the {} symbols: {}", symbols.name(), error);
+            None
+        }
+    }
+}
+
+/// A variant of the configuration matching the background of the terminal
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Theme {
+    Light,
+    Dark,
+}
+
+impl Theme {
+    /// Parse the name of a theme, `auto` and unknown names giving `None`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "light" => Some(Theme::Light),
+            "dark" => Some(Theme::Dark),
+            _ => None,
+        }
+    }
+
+    /// The name of the table holding the overrides of the theme
+    pub fn name(self) -> &'static str {
+        match self {
+            Theme::Light => "light",
+            Theme::Dark => "dark",
+        }
+    }
+
+    /// Guess the theme from `$COLORFGBG`, which is either `fg;bg` or `fg;default;bg`
+    pub fn from_colorfgbg(colorfgbg: &str) -> Option<Self> {
+        let background = colorfgbg.rsplit(';').next()?.parse::<u8>().ok()?;
+        match background {
+            7 | 9..=15 => Some(Theme::Light),
+            0..=6 | 8 => Some(Theme::Dark),
+            _ => None,
+        }
+    }
+
+    /// Select the theme from `$STARSHIP_THEME`, then the `theme.mode` option of `config`,
+    /// and finally guess it from `$COLORFGBG` if neither of them names a theme
+    pub fn select(
+        config: &Value,
+        starship_theme: Option<&str>,
+        colorfgbg: Option<&str>,
+    ) -> Option<Self> {
+        let mode = config
+            .get(THEME_KEY)
+            .and_then(|theme| theme.get("mode"))
+            .and_then(Value::as_str);
+        starship_theme
+            .and_then(Theme::from_name)
+            .or_else(|| mode.and_then(Theme::from_name))
+            .or_else(|| colorfgbg.and_then(Theme::from_colorfgbg))
+    }
+}
+
+/// What the conditions of the `[[when]]` blocks are matched against
+///
+/// Each value is only looked up once a block needs it, since finding the type of the OS can
+/// take running a command.
+#[derive(Default)]
+pub struct WhenContext {
+    hostname: OnceCell<String>,
+    os_type: OnceCell<String>,
+    shell: OnceCell<Shell>,
+    /// The environment variables to use instead of the process environment
+    env: Option<HashMap<String, String>>,
+}
+
+impl WhenContext {
+    fn hostname(&self) -> &str {
+        self.hostname
+            .get_or_init(|| gethostname::gethostname().to_string_lossy().to_lowercase())
+    }
+
+    /// The OS matches either its family, such as `linux` or `macos`, or its type, such as
+    /// `Ubuntu` or `Arch Linux`
+    fn matches_os(&self, pattern: &str) -> bool {
+        glob_matches(pattern, env::consts::OS)
+            || glob_matches(
+                pattern,
+                self.os_type
+                    .get_or_init(|| os_info::get().os_type().to_string().to_lowercase()),
+            )
+    }
+
+    fn shell(&self) -> Shell {
+        *self.shell.get_or_init(Context::get_shell)
+    }
+
+    fn env_var(&self, name: &str) -> Option<String> {
+        match &self.env {
+            Some(vars) => vars.get(name).cloned(),
+            None => env::var(name).ok(),
+        }
+    }
+
+    /// Whether every condition of a `[[when]]` block matches, each condition being a pattern
+    /// or a list of patterns of which one must match
+    fn matches(&self, block: &toml::value::Table) -> bool {
+        fn any_pattern(patterns: &Value, matches: impl Fn(&str) -> bool) -> bool {
+            match patterns {
+                Value::String(pattern) => matches(pattern),
+                Value::Array(patterns) => patterns.iter().filter_map(Value::as_str).any(matches),
+                _ => false,
+            }
+        }
+
+        let mut conditions = block
+            .iter()
+            .filter(|(key, value)| is_when_condition(key, value));
+        conditions.all(|(key, value)| match key.as_str() {
+            "hostname" => any_pattern(value, |pattern| {
+                glob_matches(&pattern.to_lowercase(), self.hostname())
+            }),
+            "os" => any_pattern(value, |pattern| self.matches_os(&pattern.to_lowercase())),
+            "shell" => any_pattern(value, |pattern| {
+                glob_matches(&pattern.to_lowercase(), self.shell().name())
+            }),
+            "env" => match value.as_table() {
+                Some(vars) => vars
+                    .iter()
+                    .all(|(name, patterns)| match self.env_var(name) {
+                        Some(var) => any_pattern(patterns, |pattern| glob_matches(pattern, &var)),
+                        None => false,
+                    }),
+                None => false,
+            },
+            _ => true,
+        })
+    }
+}
+
+/// Merge the overrides of the `[[when]]` blocks of `config` whose conditions match, in the
+/// order of the blocks
+fn apply_when_blocks(config: &mut Value, context: &WhenContext) {
+    let blocks = match config
+        .as_table_mut()
+        .and_then(|table| table.remove(WHEN_KEY))
+    {
+        Some(Value::Array(blocks)) => blocks,
+        _ => return,
+    };
+
+    for (index, block) in blocks.into_iter().enumerate() {
+        let block = match block {
+            Value::Table(block) if context.matches(&block) => block,
+            _ => continue,
+        };
+        log::debug!("Applying the overrides of `{}.{}`", WHEN_KEY, index);
+        let overrides = block
+            .into_iter()
+            .filter(|(key, value)| !is_when_condition(key, value))
+            .collect();
+        merge_config(config, Value::Table(overrides));
+    }
+}
+
+/// Match `text` against a pattern where `*` matches any characters and `?` matches a single
+/// character
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // The position of the last `*` and of the text it was matched against
+    let mut star = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                // Let the last `*` match one more character
+                Some((star_p, star_t)) => {
+                    star = Some((star_p, star_t + 1));
+                    p = star_p + 1;
+                    t = star_t + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Get the table overriding the configuration for `theme`, if any
+fn get_theme_overrides(config: &Value, theme: Theme) -> Option<&Value> {
+    config
+        .get(THEME_KEY)?
+        .get(theme.name())
+        .filter(|overrides| overrides.is_table())
+}
+
+/// The colors a terminal is able to display
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColorDepth {
+    /// No colors at all, only text attributes such as bold
+    NoColor,
+    /// The 16 standard ANSI colors
+    Ansi16,
+    /// The 256 colors of the xterm palette
+    Ansi256,
+    /// 24-bit RGB colors
+    TrueColor,
+}
+
+impl<'a> ModuleConfig<'a> for ColorDepth {
+    fn from_config(config: &'a Value) -> Option<Self> {
+        match config.as_str()? {
+            "none" => Some(ColorDepth::NoColor),
+            "16" => Some(ColorDepth::Ansi16),
+            "256" => Some(ColorDepth::Ansi256),
+            "truecolor" => Some(ColorDepth::TrueColor),
+            _ => None,
+        }
+    }
+
+    fn to_config(&self) -> Option<Value> {
+        let depth = match self {
+            ColorDepth::NoColor => "none",
+            ColorDepth::Ansi16 => "16",
+            ColorDepth::Ansi256 => "256",
+            ColorDepth::TrueColor => "truecolor",
+        };
+        Some(Value::from(depth))
+    }
+
+    fn schema() -> serde_json::Value {
+        serde_json::json!({ "type": "string", "enum": ["none", "16", "256", "truecolor"] })
+    }
+}
+
+/// The RGB values of the 16 standard colors, as displayed by xterm
+const ANSI16_RGB: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// The levels of each channel in the 6x6x6 color cube of the 256 colors palette
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl ColorDepth {
+    /// Guess the colors supported by the terminal from `$COLORTERM` and `$TERM`
+    ///
+    /// Terminals which can't be identified are assumed to support 24-bit colors.
+    pub fn detect(colorterm: Option<&str>, term: Option<&str>) -> Self {
+        if let Some("truecolor") | Some("24bit") = colorterm {
+            return ColorDepth::TrueColor;
+        }
+
+        match term.unwrap_or_default() {
+            "dumb" => ColorDepth::NoColor,
+            term if term.ends_with("-direct") => ColorDepth::TrueColor,
+            term if term.contains("256color") => ColorDepth::Ansi256,
+            "linux" | "vt100" | "vt102" | "vt220" | "ansi" | "cygwin" | "xterm" | "xterm-color"
+            | "xterm-16color" | "screen" | "tmux" | "rxvt" => ColorDepth::Ansi16,
+            _ => ColorDepth::TrueColor,
+        }
+    }
+
+    /// Map the colors of `style` to the nearest colors available at this depth
+    pub fn apply(self, style: Style) -> Style {
+        Style {
+            foreground: style.foreground.and_then(|color| self.apply_color(color)),
+            background: style.background.and_then(|color| self.apply_color(color)),
+            ..style
+        }
     }
 
-    // Check for any predefined color strings
-    // There are no predefined enums for bright colors, so we use Color::Fixed
-    let predefined_color = match color_string.to_lowercase().as_str() {
-        "black" => Some(Color::Black),
-        "red" => Some(Color::Red),
-        "green" => Some(Color::Green),
-        "yellow" => Some(Color::Yellow),
-        "blue" => Some(Color::Blue),
-        "purple" => Some(Color::Purple),
-        "cyan" => Some(Color::Cyan),
-        "white" => Some(Color::White),
-        "bright-black" => Some(Color::Fixed(8)), // "bright-black" is dark grey
-        "bright-red" => Some(Color::Fixed(9)),
-        "bright-green" => Some(Color::Fixed(10)),
-        "bright-yellow" => Some(Color::Fixed(11)),
-        "bright-blue" => Some(Color::Fixed(12)),
-        "bright-purple" => Some(Color::Fixed(13)),
-        "bright-cyan" => Some(Color::Fixed(14)),
-        "bright-white" => Some(Color::Fixed(15)),
-        _ => None,
+    fn apply_color(self, color: Color) -> Option<Color> {
+        match (self, color) {
+            (ColorDepth::NoColor, _) => None,
+            (ColorDepth::TrueColor, color) => Some(color),
+            (ColorDepth::Ansi256, Color::RGB(r, g, b)) => Some(Color::Fixed(nearest_256(r, g, b))),
+            (ColorDepth::Ansi16, Color::RGB(r, g, b)) => Some(nearest_16(r, g, b)),
+            (ColorDepth::Ansi16, Color::Fixed(n)) if n >= 16 => {
+                let (r, g, b) = fixed_to_rgb(n);
+                Some(nearest_16(r, g, b))
+            }
+            (_, color) => Some(color),
+        }
+    }
+}
+
+/// The RGB value of a color of the 256 colors palette
+fn fixed_to_rgb(n: u8) -> (u8, u8, u8) {
+    match n {
+        0..=15 => ANSI16_RGB[n as usize],
+        16..=231 => {
+            let n = n - 16;
+            (
+                CUBE_LEVELS[(n / 36) as usize],
+                CUBE_LEVELS[(n / 6 % 6) as usize],
+                CUBE_LEVELS[(n % 6) as usize],
+            )
+        }
+        _ => {
+            let level = 8 + 10 * (n - 232);
+            (level, level, level)
+        }
+    }
+}
+
+fn color_distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let square = |a: u8, b: u8| (i32::from(a) - i32::from(b)).pow(2) as u32;
+    square(r1, r2) + square(g1, g2) + square(b1, b2)
+}
+
+/// The color of the 256 colors palette closest to an RGB color, from either the color
+/// cube or the grayscale ramp
+fn nearest_256(r: u8, g: u8, b: u8) -> u8 {
+    let cube_index = |channel: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|&i| (i32::from(CUBE_LEVELS[i]) - i32::from(channel)).abs())
+            .unwrap_or_default() as u8
     };
+    let cube = 16 + 36 * cube_index(r) + 6 * cube_index(g) + cube_index(b);
 
-    if predefined_color.is_some() {
-        log::trace!("Read predefined color: {}", color_string);
+    let average = ((u16::from(r) + u16::from(g) + u16::from(b)) / 3) as u8;
+    let gray = 232 + (average.saturating_sub(3) / 10).min(23);
+
+    if color_distance((r, g, b), fixed_to_rgb(gray)) < color_distance((r, g, b), fixed_to_rgb(cube))
+    {
+        gray
     } else {
-        log::debug!("Could not parse color in string: {}", color_string);
+        cube
+    }
+}
+
+/// The standard color closest to an RGB color
+fn nearest_16(r: u8, g: u8, b: u8) -> Color {
+    let index = (0..ANSI16_RGB.len())
+        .min_by_key(|&i| color_distance((r, g, b), ANSI16_RGB[i]))
+        .unwrap_or_default();
+    match index {
+        0 => Color::Black,
+        1 => Color::Red,
+        2 => Color::Green,
+        3 => Color::Yellow,
+        4 => Color::Blue,
+        5 => Color::Purple,
+        6 => Color::Cyan,
+        7 => Color::White,
+        // There are no predefined enums for bright colors, so we use Color::Fixed
+        n => Color::Fixed(n as u8),
     }
-    predefined_color
 }
 
 #[cfg(test)]
@@ -527,6 +1807,276 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_validate_config() {
+        #[derive(Clone, ModuleConfig)]
+        struct TestConfig<'a> {
+            pub symbol: &'a str,
+            pub disabled: bool,
+            pub style: &'a str,
+            pub display: Vec<DisplayConfig<'a>>,
+        }
+
+        #[derive(Clone, ModuleConfig)]
+        struct DisplayConfig<'a> {
+            pub threshold: i64,
+            pub branch_style: &'a str,
+        }
+
+        let config = toml::toml! {
+            symbol = "T "
+            disabled = "yes"
+            style = "bold rde"
+            unknown = 1
+            display = [
+                { threshold = 10, branch_style = "red" },
+                { threshold = 20, branch_style = "fg:#1" },
+            ]
+        };
+        let mut errors = Vec::new();
+        TestConfig::validate(&config, &["test".to_string()], &mut errors);
+        let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+
+        assert_eq!(
+            messages,
+            vec![
+                "invalid value \"yes\" for `test.disabled`",
+                "invalid style string \"bold rde\" for `test.style`",
+                "unknown key `test.unknown`",
+                "invalid style string \"fg:#1\" for `test.display.1.branch_style`",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_valid_config() {
+        #[derive(Clone, ModuleConfig)]
+        struct TestConfig<'a> {
+            pub when: Option<&'a str>,
+            pub shell: VecOr<&'a str>,
+            pub style: &'a str,
+            pub aliases: HashMap<String, &'a str>,
+        }
+
+        let config = toml::toml! {
+            when = "true"
+            shell = ["bash", "--noprofile"]
+            style = "none"
+            aliases = { a = "b" }
+        };
+        let mut errors = Vec::new();
+        TestConfig::validate(&config, &[], &mut errors);
+
+        assert_eq!(errors, vec![]);
+    }
+
+    #[test]
+    fn test_config_schema() {
+        #[derive(Clone, ModuleConfig)]
+        struct TestConfig<'a> {
+            pub symbol: &'a str,
+            pub disabled: bool,
+            pub when: Option<&'a str>,
+            pub some_array: Vec<&'a str>,
+        }
+
+        let default_config = TestConfig {
+            symbol: "S ",
+            disabled: false,
+            when: None,
+            some_array: vec!["A"],
+        };
+
+        assert_eq!(
+            TestConfig::schema(),
+            serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "symbol": { "type": "string" },
+                    "disabled": { "type": "boolean" },
+                    "when": { "type": "string" },
+                    "some_array": { "type": "array", "items": { "type": "string" } },
+                },
+                "additionalProperties": false,
+            })
+        );
+        assert_eq!(
+            default_config.to_config(),
+            Some(toml::toml! {
+                symbol = "S "
+                disabled = false
+                some_array = ["A"]
+            })
+        );
+    }
+
+    #[test]
+    fn test_merge_config() {
+        let mut config = toml::toml! {
+            add_newline = false
+            [git_branch]
+            style = "red"
+            symbol = "B "
+            [directory.substitutions]
+            a = "b"
+        };
+        let other = toml::toml! {
+            [git_branch]
+            style = "green"
+            [directory.substitutions]
+            c = "d"
+            [package]
+            disabled = true
+        };
+        merge_config(&mut config, other);
+
+        assert_eq!(
+            config,
+            toml::toml! {
+                add_newline = false
+                [git_branch]
+                style = "green"
+                symbol = "B "
+                [directory.substitutions]
+                a = "b"
+                c = "d"
+                [package]
+                disabled = true
+            }
+        );
+    }
+
+    #[test]
+    fn test_config_from_env() {
+        let vars = vec![
+            ("PATH", "/bin"),
+            ("STARSHIP_CONFIG", "/tmp/starship.toml"),
+            ("STARSHIP__add_newline", "false"),
+            ("STARSHIP__package__disabled", "true"),
+            ("STARSHIP__git_branch__style", "bold red"),
+            ("STARSHIP__git_branch__truncation_length", "4"),
+            ("STARSHIP__custom__foo__symbol", "\"1\""),
+            ("STARSHIP__git_status__", "ignored"),
+        ]
+        .into_iter()
+        .map(|(name, value)| (OsString::from(name), OsString::from(value)));
+
+        assert_eq!(
+            config_from_env(vars),
+            Some(toml::toml! {
+                add_newline = false
+                [package]
+                disabled = true
+                [git_branch]
+                style = "bold red"
+                truncation_length = 4
+                [custom.foo]
+                symbol = "1"
+            })
+        );
+        assert_eq!(config_from_env(std::iter::empty()), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_config_from_env_not_utf8() {
+        use std::os::unix::ffi::OsStringExt;
+
+        let not_utf8 = || OsString::from_vec(vec![0x66, 0x6f, 0x80]);
+        let vars = vec![
+            (not_utf8(), OsString::from("value")),
+            (OsString::from("LS_COLORS"), not_utf8()),
+            (OsString::from("STARSHIP__git_branch__symbol"), not_utf8()),
+            (
+                OsString::from("STARSHIP__add_newline"),
+                OsString::from("false"),
+            ),
+        ];
+
+        assert_eq!(
+            config_from_env(vars.into_iter()),
+            Some(toml::toml! {
+                add_newline = false
+            })
+        );
+    }
+
+    #[test]
+    fn test_include_paths() {
+        let file_path = Path::new("/config/starship.toml");
+
+        let config = toml::toml! {
+            include = ["team.toml", "/etc/base.toml", "../palette.toml"]
+        };
+        assert_eq!(
+            include_paths(&config, file_path),
+            vec![
+                PathBuf::from("/config/team.toml"),
+                PathBuf::from("/etc/base.toml"),
+                PathBuf::from("/config/../palette.toml"),
+            ]
+        );
+
+        let config = toml::toml! {
+            include = "~/team.toml"
+        };
+        assert_eq!(
+            include_paths(&config, file_path),
+            vec![dirs_next::home_dir().unwrap().join("team.toml")]
+        );
+
+        assert!(include_paths(&toml::toml! { add_newline = false }, file_path).is_empty());
+    }
+
+    #[test]
+    fn test_find_line() {
+        let toml_content = r#"
+add_newline = false
+
+[git_branch]
+style = "bold red"
+
+[[battery.display]]
+threshold = 10
+
+[[battery.display]]
+threshold = 30
+style = "red"
+
+[directory]
+substitutions = { "a" = "b" }
+
+[[when]]
+shell = "bash"
+[when.character]
+success_symbol = ">"
+"#;
+        let path = |keys: &[&str]| keys.iter().map(|key| key.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(find_line(toml_content, &path(&["add_newline"])), Some(2));
+        assert_eq!(find_line(toml_content, &path(&["git_branch"])), Some(4));
+        assert_eq!(
+            find_line(toml_content, &path(&["git_branch", "style"])),
+            Some(5)
+        );
+        assert_eq!(
+            find_line(toml_content, &path(&["battery", "display", "1", "style"])),
+            Some(12)
+        );
+        assert_eq!(
+            find_line(toml_content, &path(&["directory", "substitutions", "a"])),
+            Some(15)
+        );
+        assert_eq!(
+            find_line(
+                toml_content,
+                &path(&["when", "0", "character", "success_symbol"])
+            ),
+            Some(20)
+        );
+        assert_eq!(find_line(toml_content, &path(&["username"])), None);
+    }
+
     #[test]
     fn test_load_optional_config() {
         #[derive(Clone, ModuleConfig)]
@@ -685,4 +2235,302 @@ mod tests {
             Style::new().fg(Color::Fixed(125)).on(Color::Fixed(127))
         );
     }
+
+    #[test]
+    fn table_get_styles_with_palette() {
+        let config = toml::toml! {
+            palette = "mine"
+
+            [palettes.mine]
+            accent = "#ff0000"
+            Surface = "238"
+            red = "blue"
+
+            [palettes.other]
+            accent = "green"
+        };
+        let palette = get_palette(&config);
+
+        // Palette names resolve to their color, case-insensitively
+        assert_eq!(
+            parse_style_string_with_palette("bold fg:accent bg:surface", palette),
+            Some(
+                Style::new()
+                    .bold()
+                    .fg(Color::RGB(255, 0, 0))
+                    .on(Color::Fixed(238))
+            )
+        );
+        // Palette names take precedence over the predefined colors
+        assert_eq!(
+            parse_style_string_with_palette("red", palette),
+            Some(Style::new().fg(Color::Blue))
+        );
+        // Predefined colors are still available
+        assert_eq!(
+            parse_style_string_with_palette("green", palette),
+            Some(Style::new().fg(Color::Green))
+        );
+        // Undefined names aren't valid colors
+        assert_eq!(parse_style_string_with_palette("muted", palette), None);
+        assert_eq!(parse_style_string_with_palette("accent", None), None);
+    }
+
+    #[test]
+    fn test_get_palette() {
+        let config = toml::toml! {
+            [palettes.mine]
+            accent = "red"
+        };
+        assert_eq!(get_palette(&config), None);
+
+        let config = toml::toml! {
+            palette = "missing"
+
+            [palettes.mine]
+            accent = "red"
+        };
+        assert_eq!(get_palette(&config), None);
+
+        let config = toml::toml! {
+            palette = "mine"
+
+            [palettes.mine]
+            accent = "red"
+        };
+        assert_eq!(
+            get_palette(&config).and_then(|palette| palette.get("accent")),
+            Some(&Value::from("red"))
+        );
+    }
+
+    #[test]
+    fn test_detect_color_depth() {
+        assert_eq!(
+            ColorDepth::detect(Some("truecolor"), Some("xterm-256color")),
+            ColorDepth::TrueColor
+        );
+        assert_eq!(
+            ColorDepth::detect(Some("24bit"), Some("linux")),
+            ColorDepth::TrueColor
+        );
+        assert_eq!(
+            ColorDepth::detect(None, Some("xterm-direct")),
+            ColorDepth::TrueColor
+        );
+        assert_eq!(
+            ColorDepth::detect(None, Some("screen-256color")),
+            ColorDepth::Ansi256
+        );
+        assert_eq!(ColorDepth::detect(None, Some("linux")), ColorDepth::Ansi16);
+        assert_eq!(ColorDepth::detect(None, Some("screen")), ColorDepth::Ansi16);
+        assert_eq!(ColorDepth::detect(None, Some("dumb")), ColorDepth::NoColor);
+        assert_eq!(ColorDepth::detect(None, None), ColorDepth::TrueColor);
+    }
+
+    #[test]
+    fn test_apply_color_depth() {
+        let style = Color::RGB(255, 128, 0).bold().on(Color::Fixed(238));
+
+        assert_eq!(ColorDepth::TrueColor.apply(style), style);
+        assert_eq!(
+            ColorDepth::Ansi256.apply(style),
+            Color::Fixed(208).bold().on(Color::Fixed(238))
+        );
+        assert_eq!(
+            ColorDepth::Ansi16.apply(style),
+            Color::Yellow.bold().on(Color::Fixed(8))
+        );
+        assert_eq!(ColorDepth::NoColor.apply(style), Style::new().bold());
+
+        // The standard colors are available at every depth
+        let style = Color::Green.on(Color::Fixed(12));
+        assert_eq!(ColorDepth::Ansi16.apply(style), style);
+        assert_eq!(ColorDepth::Ansi256.apply(style), style);
+    }
+
+    #[test]
+    fn test_nearest_256_color() {
+        assert_eq!(nearest_256(0, 0, 0), 16);
+        assert_eq!(nearest_256(255, 255, 255), 231);
+        assert_eq!(nearest_256(0x5f, 0x87, 0xaf), 67);
+        // Grays are closer to the grayscale ramp than to the color cube
+        assert_eq!(nearest_256(0x80, 0x80, 0x80), 244);
+        assert_eq!(nearest_256(0x12, 0x12, 0x12), 233);
+    }
+
+    #[test]
+    fn test_from_color_depth() {
+        let config = Value::from("256");
+        assert_eq!(ColorDepth::from_config(&config), Some(ColorDepth::Ansi256));
+        let config = Value::from("none");
+        assert_eq!(ColorDepth::from_config(&config), Some(ColorDepth::NoColor));
+        let config = Value::from("8");
+        assert_eq!(ColorDepth::from_config(&config), None);
+    }
+
+    #[test]
+    fn test_theme_from_colorfgbg() {
+        assert_eq!(Theme::from_colorfgbg("15;0"), Some(Theme::Dark));
+        assert_eq!(Theme::from_colorfgbg("0;15"), Some(Theme::Light));
+        assert_eq!(Theme::from_colorfgbg("0;default;7"), Some(Theme::Light));
+        assert_eq!(Theme::from_colorfgbg("7;default;8"), Some(Theme::Dark));
+        assert_eq!(Theme::from_colorfgbg("0;default"), None);
+        assert_eq!(Theme::from_colorfgbg(""), None);
+    }
+
+    #[test]
+    fn test_select_theme() {
+        let config = toml::toml! {
+            [theme]
+            mode = "light"
+        };
+        assert_eq!(
+            Theme::select(&config, None, Some("15;0")),
+            Some(Theme::Light)
+        );
+        assert_eq!(
+            Theme::select(&config, Some("dark"), Some("0;15")),
+            Some(Theme::Dark)
+        );
+
+        let config = toml::toml! {
+            [theme]
+            mode = "auto"
+        };
+        assert_eq!(
+            Theme::select(&config, None, Some("15;0")),
+            Some(Theme::Dark)
+        );
+        assert_eq!(
+            Theme::select(&config, Some("auto"), Some("0;15")),
+            Some(Theme::Light)
+        );
+        assert_eq!(Theme::select(&config, None, None), None);
+    }
+
+    #[test]
+    fn test_theme_overrides() {
+        let config = toml::toml! {
+            [theme.dark.git_branch]
+            style = "bold yellow"
+        };
+        assert_eq!(
+            get_theme_overrides(&config, Theme::Dark),
+            Some(&toml::toml! {
+                [git_branch]
+                style = "bold yellow"
+            })
+        );
+        assert_eq!(get_theme_overrides(&config, Theme::Light), None);
+    }
+
+    #[test]
+    fn test_symbols_layer() {
+        let layer = symbols_layer(&toml::toml! { symbols = "ascii" }).unwrap();
+        assert_eq!(layer.source, ConfigSource::Symbols(SymbolSet::Ascii));
+        assert_eq!(
+            layer
+                .config
+                .get("character")
+                .and_then(|c| c.get("success_symbol")),
+            Some(&Value::from("[>](bold green)"))
+        );
+
+        let layer = symbols_layer(&toml::toml! { symbols = "nerd-font" }).unwrap();
+        assert_eq!(layer.source, ConfigSource::Symbols(SymbolSet::NerdFont));
+
+        assert!(symbols_layer(&toml::toml! { symbols = "unicode" }).is_none());
+        assert!(symbols_layer(&toml::toml! { symbols = "emoji" }).is_none());
+        assert!(symbols_layer(&Value::Table(toml::value::Table::new())).is_none());
+    }
+
+    fn when_context(hostname: &str, shell: Shell, env: &[(&str, &str)]) -> WhenContext {
+        WhenContext {
+            hostname: OnceCell::from(hostname.to_string()),
+            os_type: OnceCell::from("ubuntu".to_string()),
+            shell: OnceCell::from(shell),
+            env: Some(
+                env.iter()
+                    .map(|(name, value)| (name.to_string(), value.to_string()))
+                    .collect(),
+            ),
+        }
+    }
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("prod-*", "prod-1.example.com"));
+        assert!(glob_matches("*.example.com", "prod-1.example.com"));
+        assert!(glob_matches("prod-?", "prod-1"));
+        assert!(glob_matches("*-*-*", "a-b-c"));
+        assert!(glob_matches("*", ""));
+        assert!(!glob_matches("prod-?", "prod-10"));
+        assert!(!glob_matches("prod-*", "dev-1"));
+        assert!(!glob_matches("", "prod"));
+    }
+
+    #[test]
+    fn test_when_context_matches() {
+        let context = when_context("prod-1", Shell::Bash, &[("CI", "true")]);
+        let matches = |block: Value| context.matches(block.as_table().unwrap());
+
+        assert!(matches(toml::toml! { hostname = "PROD-*" }));
+        assert!(matches(
+            toml::toml! { hostname = "prod-*" shell = ["zsh", "bash"] }
+        ));
+        assert!(matches(toml::toml! { os = "Ubuntu" }));
+        assert!(matches(toml::toml! { env = { CI = "*" } }));
+        assert!(matches(
+            toml::toml! { shell = "bash" [character] disabled = true }
+        ));
+        assert!(matches(toml::toml! { [hostname] ssh_only = false }));
+        assert!(!matches(toml::toml! { hostname = "prod-*" shell = "zsh" }));
+        assert!(!matches(toml::toml! { os = "fedora" }));
+        assert!(!matches(toml::toml! { env = { CI = "false" } }));
+        assert!(!matches(toml::toml! { env = { SSH_CONNECTION = "*" } }));
+        assert!(!matches(toml::toml! { shell = 1 }));
+    }
+
+    #[test]
+    fn test_apply_when_blocks() {
+        let context = when_context("laptop", Shell::Zsh, &[]);
+        let mut config = toml::toml! {
+            add_newline = false
+            [character]
+            success_symbol = ">"
+
+            [[when]]
+            shell = "zsh"
+            add_newline = true
+            [when.character]
+            success_symbol = "zsh>"
+            [when.directory]
+            truncation_length = 1
+
+            [[when]]
+            hostname = "prod-*"
+            [when.character]
+            success_symbol = "prod>"
+
+            [[when]]
+            hostname = "laptop"
+            [when.directory]
+            truncation_length = 2
+            [when.hostname]
+            ssh_only = false
+        };
+        apply_when_blocks(&mut config, &context);
+
+        let expected = toml::toml! {
+            add_newline = true
+            [character]
+            success_symbol = "zsh>"
+            [directory]
+            truncation_length = 2
+            [hostname]
+            ssh_only = false
+        };
+        assert_eq!(config, expected);
+    }
 }
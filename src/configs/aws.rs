@@ -1,7 +1,6 @@
-use crate::config::{ModuleConfig, RootModuleConfig, SegmentConfig};
+use crate::config::{ModuleConfig, RootModuleConfig};
 use std::collections::HashMap;
 
-use ansi_term::{Color, Style};
 use starship_module_config_derive::ModuleConfig;
 
 #[derive(Clone, PartialEq)]
@@ -13,10 +12,9 @@ pub enum AwsItems {
 
 #[derive(Clone, ModuleConfig)]
 pub struct AwsConfig<'a> {
-    pub symbol: SegmentConfig<'a>,
-    pub profile: SegmentConfig<'a>,
-    pub region: SegmentConfig<'a>,
-    pub style: Style,
+    pub format: &'a str,
+    pub symbol: &'a str,
+    pub style: &'a str,
     pub disabled: bool,
     pub displayed_items: AwsItems,
     pub region_aliases: HashMap<String, &'a str>,
@@ -25,10 +23,9 @@ pub struct AwsConfig<'a> {
 impl<'a> RootModuleConfig<'a> for AwsConfig<'a> {
     fn new() -> Self {
         AwsConfig {
-            symbol: SegmentConfig::new("☁️  "),
-            profile: SegmentConfig::default(),
-            region: SegmentConfig::default(),
-            style: Color::Yellow.bold(),
+            format: "on [$symbol$all]($style) ",
+            symbol: "☁️  ",
+            style: "bold yellow",
             disabled: false,
             displayed_items: AwsItems::All,
             region_aliases: HashMap::new(),
@@ -45,4 +42,17 @@ impl<'a> ModuleConfig<'a> for AwsItems {
             _ => None,
         }
     }
+
+    fn to_config(&self) -> Option<toml::Value> {
+        let item = match self {
+            AwsItems::All => "all",
+            AwsItems::Region => "region",
+            AwsItems::Profile => "profile",
+        };
+        Some(toml::Value::from(item))
+    }
+
+    fn schema() -> serde_json::Value {
+        serde_json::json!({ "type": "string", "enum": ["all", "region", "profile"] })
+    }
 }
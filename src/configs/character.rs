@@ -1,28 +1,23 @@
-use crate::config::{ModuleConfig, RootModuleConfig, SegmentConfig};
+use crate::config::{ModuleConfig, RootModuleConfig};
 
-use ansi_term::{Color, Style};
 use starship_module_config_derive::ModuleConfig;
 
 #[derive(Clone, ModuleConfig)]
 pub struct CharacterConfig<'a> {
-    pub symbol: SegmentConfig<'a>,
-    pub error_symbol: SegmentConfig<'a>,
-    pub vicmd_symbol: SegmentConfig<'a>,
-    pub use_symbol_for_status: bool,
-    pub style_success: Style,
-    pub style_failure: Style,
+    pub format: &'a str,
+    pub success_symbol: &'a str,
+    pub error_symbol: &'a str,
+    pub vicmd_symbol: &'a str,
     pub disabled: bool,
 }
 
 impl<'a> RootModuleConfig<'a> for CharacterConfig<'a> {
     fn new() -> Self {
         CharacterConfig {
-            symbol: SegmentConfig::new("❯"),
-            error_symbol: SegmentConfig::new("✖"),
-            vicmd_symbol: SegmentConfig::new("❮"),
-            use_symbol_for_status: false,
-            style_success: Color::Green.bold(),
-            style_failure: Color::Red.bold(),
+            format: "$symbol ",
+            success_symbol: "[❯](bold green)",
+            error_symbol: "[❯](bold red)",
+            vicmd_symbol: "[❮](bold green)",
             disabled: false,
         }
     }
@@ -1,14 +1,13 @@
-use crate::config::{ModuleConfig, RootModuleConfig, SegmentConfig};
+use crate::config::{ModuleConfig, RootModuleConfig};
 
-use ansi_term::{Color, Style};
 use starship_module_config_derive::ModuleConfig;
 
 #[derive(Clone, ModuleConfig)]
 pub struct CondaConfig<'a> {
     pub truncation_length: usize,
-    pub symbol: SegmentConfig<'a>,
-    pub environment: SegmentConfig<'a>,
-    pub style: Style,
+    pub format: &'a str,
+    pub symbol: &'a str,
+    pub style: &'a str,
     pub disabled: bool,
 }
 
@@ -16,15 +15,9 @@ impl<'a> RootModuleConfig<'a> for CondaConfig<'a> {
     fn new() -> Self {
         CondaConfig {
             truncation_length: 1,
-            symbol: SegmentConfig {
-                value: "C ",
-                style: None,
-            },
-            environment: SegmentConfig {
-                value: "",
-                style: None,
-            },
-            style: Color::Green.bold(),
+            format: "via [$symbol$environment]($style) ",
+            symbol: "C ",
+            style: "green bold",
             disabled: false,
         }
     }
@@ -1,7 +1,6 @@
 use crate::config::{ModuleConfig, RootModuleConfig};
 use std::collections::HashMap;
 
-use ansi_term::{Color, Style};
 use starship_module_config_derive::ModuleConfig;
 
 #[derive(Clone, ModuleConfig)]
@@ -11,8 +10,8 @@ pub struct DirectoryConfig<'a> {
     pub substitutions: HashMap<String, &'a str>,
     pub fish_style_pwd_dir_length: i64,
     pub use_logical_path: bool,
-    pub prefix: &'a str,
-    pub style: Style,
+    pub format: &'a str,
+    pub style: &'a str,
     pub disabled: bool,
 }
 
@@ -24,8 +23,8 @@ impl<'a> RootModuleConfig<'a> for DirectoryConfig<'a> {
             fish_style_pwd_dir_length: 0,
             substitutions: HashMap::new(),
             use_logical_path: true,
-            prefix: "in ",
-            style: Color::Cyan.bold(),
+            format: "[$path]($style) ",
+            style: "cyan bold",
             disabled: false,
         }
     }
@@ -1,28 +1,25 @@
-use crate::config::{ModuleConfig, RootModuleConfig, SegmentConfig};
+use crate::config::{ModuleConfig, RootModuleConfig};
 
-use ansi_term::{Color, Style};
 use starship_module_config_derive::ModuleConfig;
 
 #[derive(Clone, ModuleConfig)]
 pub struct EnvVarConfig<'a> {
-    pub symbol: Option<SegmentConfig<'a>>,
+    pub symbol: &'a str,
+    pub style: &'a str,
     pub variable: Option<&'a str>,
     pub default: Option<&'a str>,
-    pub prefix: &'a str,
-    pub suffix: &'a str,
-    pub style: Style,
+    pub format: &'a str,
     pub disabled: bool,
 }
 
 impl<'a> RootModuleConfig<'a> for EnvVarConfig<'a> {
     fn new() -> Self {
         EnvVarConfig {
-            symbol: None,
+            symbol: "",
+            style: "black bold dimmed",
             variable: None,
             default: None,
-            prefix: "",
-            suffix: "",
-            style: Color::Black.bold().dimmed(),
+            format: "with [$symbol$env_value]($style) ",
             disabled: false,
         }
     }
@@ -0,0 +1,20 @@
+use crate::config::{ModuleConfig, RootModuleConfig};
+
+use starship_module_config_derive::ModuleConfig;
+
+#[derive(Clone, ModuleConfig)]
+pub struct FillConfig<'a> {
+    pub symbol: &'a str,
+    pub style: &'a str,
+    pub disabled: bool,
+}
+
+impl<'a> RootModuleConfig<'a> for FillConfig<'a> {
+    fn new() -> Self {
+        FillConfig {
+            symbol: ".",
+            style: "bold black",
+            disabled: false,
+        }
+    }
+}
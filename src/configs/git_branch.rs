@@ -1,26 +1,29 @@
-use crate::config::{ModuleConfig, RootModuleConfig, SegmentConfig};
+use crate::config::{ModuleConfig, RootModuleConfig};
 
-use ansi_term::{Color, Style};
 use starship_module_config_derive::ModuleConfig;
 
 #[derive(Clone, ModuleConfig)]
 pub struct GitBranchConfig<'a> {
-    pub symbol: SegmentConfig<'a>,
+    pub format: &'a str,
+    pub symbol: &'a str,
+    pub style: &'a str,
+    pub clean_style: &'a str,
+    pub dirty_style: &'a str,
     pub truncation_length: i64,
     pub truncation_symbol: &'a str,
-    pub branch_name: SegmentConfig<'a>,
-    pub style: Style,
     pub disabled: bool,
 }
 
 impl<'a> RootModuleConfig<'a> for GitBranchConfig<'a> {
     fn new() -> Self {
         GitBranchConfig {
-            symbol: SegmentConfig::new(" "),
+            format: "on [$symbol$branch]($style) ",
+            symbol: " ",
+            style: "bold purple",
+            clean_style: "bold purple",
+            dirty_style: "bold red",
             truncation_length: std::i64::MAX,
             truncation_symbol: "…",
-            branch_name: SegmentConfig::default(),
-            style: Color::Purple.bold(),
             disabled: false,
         }
     }
@@ -1,15 +1,12 @@
-use crate::config::{ModuleConfig, RootModuleConfig, SegmentConfig};
+use crate::config::{ModuleConfig, RootModuleConfig};
 
-use ansi_term::{Color, Style};
 use starship_module_config_derive::ModuleConfig;
 
 #[derive(Clone, ModuleConfig)]
 pub struct GitCommitConfig<'a> {
     pub commit_hash_length: usize,
-    pub hash: SegmentConfig<'a>,
-    pub prefix: &'a str,
-    pub suffix: &'a str,
-    pub style: Style,
+    pub format: &'a str,
+    pub style: &'a str,
     pub only_detached: bool,
     pub disabled: bool,
 }
@@ -19,10 +16,8 @@ impl<'a> RootModuleConfig<'a> for GitCommitConfig<'a> {
         GitCommitConfig {
             // be consistent with git by default, which has DEFAULT_ABBREV set to 7
             commit_hash_length: 7,
-            hash: SegmentConfig::default(),
-            prefix: "(",
-            suffix: ") ",
-            style: Color::Green.bold(),
+            format: "[\\($hash\\)]($style) ",
+            style: "green bold",
             only_detached: true,
             disabled: false,
         }
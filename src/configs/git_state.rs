@@ -1,34 +1,35 @@
-use crate::config::{ModuleConfig, RootModuleConfig, SegmentConfig};
+use crate::config::{ModuleConfig, RootModuleConfig};
 
-use ansi_term::{Color, Style};
 use starship_module_config_derive::ModuleConfig;
 
 #[derive(Clone, ModuleConfig)]
 pub struct GitStateConfig<'a> {
-    pub rebase: SegmentConfig<'a>,
-    pub merge: SegmentConfig<'a>,
-    pub revert: SegmentConfig<'a>,
-    pub cherry_pick: SegmentConfig<'a>,
-    pub bisect: SegmentConfig<'a>,
-    pub am: SegmentConfig<'a>,
-    pub am_or_rebase: SegmentConfig<'a>,
-    pub progress_divider: SegmentConfig<'a>,
-    pub style: Style,
+    pub rebase: &'a str,
+    pub merge: &'a str,
+    pub revert: &'a str,
+    pub cherry_pick: &'a str,
+    pub bisect: &'a str,
+    pub am: &'a str,
+    pub am_or_rebase: &'a str,
+    pub progress_divider: &'a str,
+    pub style: &'a str,
+    pub format: &'a str,
     pub disabled: bool,
 }
 
 impl<'a> RootModuleConfig<'a> for GitStateConfig<'a> {
     fn new() -> Self {
         GitStateConfig {
-            rebase: SegmentConfig::new("REBASING"),
-            merge: SegmentConfig::new("MERGING"),
-            revert: SegmentConfig::new("REVERTING"),
-            cherry_pick: SegmentConfig::new("CHERRY-PICKING"),
-            bisect: SegmentConfig::new("BISECTING"),
-            am: SegmentConfig::new("AM"),
-            am_or_rebase: SegmentConfig::new("AM/REBASE"),
-            progress_divider: SegmentConfig::new("/"),
-            style: Color::Yellow.bold(),
+            rebase: "REBASING",
+            merge: "MERGING",
+            revert: "REVERTING",
+            cherry_pick: "CHERRY-PICKING",
+            bisect: "BISECTING",
+            am: "AM",
+            am_or_rebase: "AM/REBASE",
+            progress_divider: "/",
+            style: "bold yellow",
+            format: "\\([$state$progress]($style)\\) ",
             disabled: false,
         }
     }
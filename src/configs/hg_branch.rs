@@ -1,26 +1,25 @@
-use crate::config::{ModuleConfig, RootModuleConfig, SegmentConfig};
+use crate::config::{ModuleConfig, RootModuleConfig};
 
-use ansi_term::{Color, Style};
 use starship_module_config_derive::ModuleConfig;
 
 #[derive(Clone, ModuleConfig)]
 pub struct HgBranchConfig<'a> {
-    pub symbol: SegmentConfig<'a>,
+    pub symbol: &'a str,
+    pub style: &'a str,
+    pub format: &'a str,
     pub truncation_length: i64,
     pub truncation_symbol: &'a str,
-    pub branch_name: SegmentConfig<'a>,
-    pub style: Style,
     pub disabled: bool,
 }
 
 impl<'a> RootModuleConfig<'a> for HgBranchConfig<'a> {
     fn new() -> Self {
         HgBranchConfig {
-            symbol: SegmentConfig::new(" "),
+            symbol: " ",
+            style: "bold purple",
+            format: "on [$symbol$branch]($style) ",
             truncation_length: std::i64::MAX,
             truncation_symbol: "…",
-            branch_name: SegmentConfig::default(),
-            style: Color::Purple.bold(),
             disabled: true,
         }
     }
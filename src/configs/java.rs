@@ -1,20 +1,21 @@
-use crate::config::{ModuleConfig, RootModuleConfig, SegmentConfig};
+use crate::config::{ModuleConfig, RootModuleConfig};
 
-use ansi_term::{Color, Style};
 use starship_module_config_derive::ModuleConfig;
 
 #[derive(Clone, ModuleConfig)]
 pub struct JavaConfig<'a> {
-    pub symbol: SegmentConfig<'a>,
-    pub style: Style,
+    pub format: &'a str,
+    pub symbol: &'a str,
+    pub style: &'a str,
     pub disabled: bool,
 }
 
 impl<'a> RootModuleConfig<'a> for JavaConfig<'a> {
     fn new() -> Self {
         JavaConfig {
-            symbol: SegmentConfig::new("☕ "),
-            style: Color::Red.dimmed(),
+            format: "via [$symbol$version]($style) ",
+            symbol: "☕ ",
+            style: "dimmed red",
             disabled: false,
         }
     }
@@ -1,15 +1,13 @@
-use crate::config::{ModuleConfig, RootModuleConfig, SegmentConfig};
+use crate::config::{ModuleConfig, RootModuleConfig};
 
-use ansi_term::{Color, Style};
 use starship_module_config_derive::ModuleConfig;
 use std::collections::HashMap;
 
 #[derive(Clone, ModuleConfig)]
 pub struct KubernetesConfig<'a> {
-    pub symbol: SegmentConfig<'a>,
-    pub context: SegmentConfig<'a>,
-    pub namespace: SegmentConfig<'a>,
-    pub style: Style,
+    pub symbol: &'a str,
+    pub format: &'a str,
+    pub style: &'a str,
     pub disabled: bool,
     pub context_aliases: HashMap<String, &'a str>,
 }
@@ -17,10 +15,9 @@ pub struct KubernetesConfig<'a> {
 impl<'a> RootModuleConfig<'a> for KubernetesConfig<'a> {
     fn new() -> Self {
         KubernetesConfig {
-            symbol: SegmentConfig::new("☸ "),
-            context: SegmentConfig::default(),
-            namespace: SegmentConfig::default(),
-            style: Color::Cyan.bold(),
+            symbol: "☸ ",
+            format: "on [$symbol$context( \\($namespace\\))]($style) ",
+            style: "cyan bold",
             disabled: true,
             context_aliases: HashMap::new(),
         }
@@ -0,0 +1,679 @@
+//! Migrations of the options replaced by past releases
+//!
+//! Unknown keys are ignored when loading the config of a module, so an option renamed by a
+//! release silently stops working. Each migration lists the keys it replaced, so that they can
+//! be reported, and rewrites them into the current options for `starship config migrate`.
+
+use super::custom::CustomConfig;
+use super::resolve_module;
+use super::StarshipRootConfig;
+use crate::config::{self, ModuleConfig, RootModuleConfig, Theme};
+use regex::{NoExpand, Regex};
+use toml::Value;
+use toml_edit::{Document, Item, TableLike};
+
+/// Keys of a module replaced by a release, and how to rewrite them
+pub struct Migration {
+    /// The release which replaced the keys
+    pub version: &'static str,
+    /// The module whose keys were replaced, `None` for every module, or `ROOT` for the root
+    /// options
+    pub module: Option<&'static str>,
+    /// The replaced keys
+    pub keys: &'static [&'static str],
+    /// The options replacing the keys, for the messages reporting them
+    pub replacement: &'static str,
+    /// Rewrite the replaced keys of the table configuring a module
+    migrate: fn(module: &str, table: &mut dyn TableLike),
+}
+
+impl Migration {
+    fn applies_to(&self, module: &str) -> bool {
+        match self.module {
+            None => module != ROOT,
+            Some(migration_module) => migration_module == module,
+        }
+    }
+}
+
+/// The name given to the table of the root options, which isn't a module
+const ROOT: &str = "";
+
+/// The statuses of `git_status` which showed their count with a `<status>_count` table
+const GIT_STATUS_COUNTS: &[&str] = &[
+    "conflicted",
+    "deleted",
+    "renamed",
+    "modified",
+    "staged",
+    "untracked",
+];
+
+/// Every migration, from the oldest release to the newest one
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: "0.44.0",
+        module: None,
+        keys: &["prefix", "suffix"],
+        replacement: "`format`",
+        migrate: migrate_prefix_suffix,
+    },
+    Migration {
+        version: "0.44.0",
+        module: Some("character"),
+        keys: &[
+            "symbol",
+            "use_symbol_for_status",
+            "style_success",
+            "style_failure",
+        ],
+        replacement: "`success_symbol` and `error_symbol`",
+        migrate: migrate_character,
+    },
+    Migration {
+        version: "0.44.0",
+        module: Some("memory_usage"),
+        keys: &["show_percentage", "show_swap", "separator"],
+        replacement: "the `$ram_pct`, `$swap` and `$swap_pct` variables of `format`",
+        migrate: migrate_memory_usage,
+    },
+    Migration {
+        version: "0.44.0",
+        module: Some(ROOT),
+        keys: &["prompt_order"],
+        replacement: "`format`",
+        migrate: migrate_prompt_order,
+    },
+    Migration {
+        version: "0.44.0",
+        module: Some("git_status"),
+        keys: &[
+            "show_sync_count",
+            "conflicted_count",
+            "deleted_count",
+            "renamed_count",
+            "modified_count",
+            "staged_count",
+            "untracked_count",
+        ],
+        replacement: "the `$count` variable of the symbols",
+        migrate: migrate_git_status,
+    },
+    Migration {
+        version: "0.44.0",
+        module: Some("nix_shell"),
+        keys: &["use_name"],
+        replacement: "the `$name` variable of `format`",
+        migrate: migrate_nix_shell,
+    },
+    Migration {
+        version: "0.44.0",
+        module: Some("terraform"),
+        keys: &["show_version"],
+        replacement: "the `$version` variable of `format`",
+        migrate: migrate_terraform,
+    },
+];
+
+/// A replaced key found in a configuration
+pub struct Deprecation {
+    /// The list of keys leading to the replaced key
+    pub path: Vec<String>,
+    pub migration: &'static Migration,
+}
+
+impl Deprecation {
+    /// The path to the replaced key, with keys separated by dots
+    pub fn key(&self) -> String {
+        self.path.join(".")
+    }
+}
+
+/// Find the keys of `config` replaced by a migration
+pub fn find_deprecated(config: &Value) -> Vec<Deprecation> {
+    let mut deprecations = Vec::new();
+    for (path, module) in module_tables(config) {
+        let table = match path
+            .iter()
+            .try_fold(config, |value, key| get_value(value, key))
+        {
+            Some(table) => table,
+            None => continue,
+        };
+        for migration in MIGRATIONS.iter().filter(|m| m.applies_to(&module)) {
+            for key in migration
+                .keys
+                .iter()
+                .filter(|key| table.get(**key).is_some())
+            {
+                let mut key_path = path.clone();
+                key_path.push(key.to_string());
+                deprecations.push(Deprecation {
+                    path: key_path,
+                    migration,
+                });
+            }
+        }
+    }
+    deprecations
+}
+
+/// Rewrite the replaced keys of a configuration file, returning the keys which were replaced
+///
+/// The rest of the document, including its comments, is left as it is.
+pub fn migrate(document: &mut Document) -> Vec<Deprecation> {
+    let config = match toml::from_str::<Value>(&document.to_string()) {
+        Ok(config) => config,
+        Err(_) => return Vec::new(),
+    };
+    let deprecations = find_deprecated(&config);
+
+    for (path, module) in module_tables(&config) {
+        let table = path
+            .iter()
+            .try_fold(document.as_item_mut(), |item, key| get_item_mut(item, key))
+            .and_then(Item::as_table_like_mut);
+        let table = match table {
+            Some(table) => table,
+            None => continue,
+        };
+        for migration in MIGRATIONS.iter().filter(|m| m.applies_to(&module)) {
+            if migration.keys.iter().any(|key| table.contains_key(key)) {
+                (migration.migrate)(&module, table);
+            }
+        }
+    }
+    deprecations
+}
+
+/// Look up a key of a table, or an index of an array such as the `[[when]]` blocks
+fn get_value<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    match value {
+        Value::Array(values) => values.get(key.parse::<usize>().ok()?),
+        _ => value.get(key),
+    }
+}
+
+fn get_item_mut<'a>(item: &'a mut Item, key: &str) -> Option<&'a mut Item> {
+    match key.parse::<usize>() {
+        Ok(index) if item.is_array_of_tables() => item.get_mut(index),
+        _ => item.get_mut(key),
+    }
+}
+
+/// Find the tables configuring a module, with the name of the module
+///
+/// This includes the custom modules and the modules in the overrides of each theme.
+fn module_tables(config: &Value) -> Vec<(Vec<String>, String)> {
+    let mut tables = Vec::new();
+    add_module_tables(config, &[], &mut tables);
+    tables
+}
+
+fn add_module_tables(config: &Value, path: &[String], tables: &mut Vec<(Vec<String>, String)>) {
+    let table = match config.as_table() {
+        Some(table) => table,
+        None => return,
+    };
+    tables.push((path.to_vec(), ROOT.to_string()));
+
+    for (name, value) in table.iter() {
+        let mut module_path = path.to_vec();
+        module_path.push(name.clone());
+        match name.as_str() {
+            "custom" => {
+                for custom_name in value.as_table().into_iter().flat_map(|table| table.keys()) {
+                    let mut custom_path = module_path.clone();
+                    custom_path.push(custom_name.clone());
+                    tables.push((custom_path, "custom".to_string()));
+                }
+            }
+            config::WHEN_KEY if path.is_empty() => {
+                for (index, block) in value.as_array().into_iter().flatten().enumerate() {
+                    let mut block_path = module_path.clone();
+                    block_path.push(index.to_string());
+                    add_module_tables(block, &block_path, tables);
+                }
+            }
+            config::THEME_KEY if path.is_empty() => {
+                for (theme, overrides) in value.as_table().into_iter().flatten() {
+                    if Theme::from_name(theme).is_some() {
+                        let mut theme_path = module_path.clone();
+                        theme_path.push(theme.clone());
+                        add_module_tables(overrides, &theme_path, tables);
+                    }
+                }
+            }
+            module if resolve_module(module, None).is_some() => {
+                tables.push((module_path, module.to_string()))
+            }
+            _ => {}
+        }
+    }
+}
+
+fn remove_str(table: &mut dyn TableLike, key: &str) -> Option<String> {
+    table
+        .remove(key)
+        .and_then(|item| item.as_str().map(String::from))
+}
+
+fn remove_bool(table: &mut dyn TableLike, key: &str) -> Option<bool> {
+    table.remove(key).and_then(|item| item.as_bool())
+}
+
+/// The value of a string option of a module table, or its default value
+fn current_option(module: &str, table: &dyn TableLike, key: &str) -> String {
+    if let Some(value) = table.get(key).and_then(Item::as_str) {
+        return value.to_string();
+    }
+
+    let defaults = match module {
+        ROOT => StarshipRootConfig::new().to_config(),
+        "custom" => CustomConfig::new().to_config(),
+        module => resolve_module(module, None),
+    };
+    defaults
+        .as_ref()
+        .and_then(|defaults| defaults.get(key))
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// The format of a module table, or the default format of the module
+fn current_format(module: &str, table: &dyn TableLike) -> String {
+    current_option(module, table, "format")
+}
+
+/// Replace a variable of a format string, whether it's written `$name` or `${name}`
+fn replace_variable(format: &str, name: &str, replacement: &str) -> String {
+    let pattern = format!(r"\$({0}|\{{{0}\}})\b", regex::escape(name));
+    let variable = Regex::new(&pattern).expect("the pattern is valid");
+    variable
+        .replace_all(format, NoExpand(replacement))
+        .into_owned()
+}
+
+/// Split a format string into its leading text, its variables and groups, and its trailing
+/// text
+fn split_format(format: &str) -> (&str, &str, &str) {
+    let mut start = None;
+    let mut end = 0;
+    let mut chars = format.char_indices().peekable();
+
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '[' | ']' | '(' | ')' => {
+                start.get_or_insert(index);
+                end = index + 1;
+            }
+            '$' => {
+                start.get_or_insert(index);
+                end = index + 1;
+                let braced = matches!(chars.peek(), Some((_, '{')));
+                while let Some(&(index, c)) = chars.peek() {
+                    if !(braced || c.is_ascii_alphanumeric() || c == '_') {
+                        break;
+                    }
+                    chars.next();
+                    end = index + c.len_utf8();
+                    if c == '}' {
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    match start {
+        Some(start) => (&format[..start], &format[start..end], &format[end..]),
+        None => (format, "", ""),
+    }
+}
+
+/// Escape the characters of `text` which have a meaning in format strings
+fn escape_format_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '[' | ']' | '(' | ')' | '$' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// `prefix` and `suffix` were the text around each module, which is now part of its `format`
+fn migrate_prefix_suffix(module: &str, table: &mut dyn TableLike) {
+    let prefix = remove_str(table, "prefix");
+    let suffix = remove_str(table, "suffix");
+    let format = current_format(module, table);
+
+    let (default_prefix, body, default_suffix) = split_format(&format);
+    let format = format!(
+        "{}{}{}",
+        prefix.map_or_else(|| default_prefix.to_string(), |p| escape_format_text(&p)),
+        body,
+        suffix.map_or_else(|| default_suffix.to_string(), |s| escape_format_text(&s)),
+    );
+    table.insert("format", toml_edit::value(format));
+}
+
+/// The symbols of `character` were plain text styled by `style_success` and `style_failure`,
+/// and `error_symbol` was only used with `use_symbol_for_status`
+fn migrate_character(_module: &str, table: &mut dyn TableLike) {
+    let symbol = remove_str(table, "symbol").unwrap_or_else(|| "❯".to_string());
+    let use_symbol_for_status = remove_bool(table, "use_symbol_for_status").unwrap_or(false);
+    let success_style =
+        remove_str(table, "style_success").unwrap_or_else(|| "bold green".to_string());
+    let failure_style =
+        remove_str(table, "style_failure").unwrap_or_else(|| "bold red".to_string());
+
+    let is_plain = |symbol: &str| split_format(symbol).1.is_empty();
+    let styled = |symbol: &str, style: &str| {
+        toml_edit::value(format!("[{}]({})", escape_format_text(symbol), style))
+    };
+
+    if table.get("success_symbol").is_none() {
+        table.insert("success_symbol", styled(&symbol, &success_style));
+    }
+
+    let error_symbol = match table.get("error_symbol").and_then(Item::as_str) {
+        // A symbol with a style is already in the current form
+        Some(error_symbol) if !is_plain(error_symbol) => None,
+        Some(error_symbol) if use_symbol_for_status => Some(error_symbol.to_string()),
+        None if use_symbol_for_status => Some("✖".to_string()),
+        _ => Some(symbol),
+    };
+    if let Some(error_symbol) = error_symbol {
+        table.insert("error_symbol", styled(&error_symbol, &failure_style));
+    }
+
+    let vicmd_symbol = table.get("vicmd_symbol").and_then(Item::as_str);
+    if let Some(vicmd_symbol) = vicmd_symbol.filter(|symbol| is_plain(symbol)) {
+        let vicmd_symbol = styled(vicmd_symbol, &success_style);
+        table.insert("vicmd_symbol", vicmd_symbol);
+    }
+}
+
+/// `show_percentage`, `show_swap` and `separator` selected the values shown by `memory_usage`,
+/// which are now variables of its `format`
+fn migrate_memory_usage(module: &str, table: &mut dyn TableLike) {
+    let show_percentage = remove_bool(table, "show_percentage").unwrap_or(false);
+    let show_swap = remove_bool(table, "show_swap").unwrap_or(true);
+    let separator = remove_str(table, "separator").unwrap_or_else(|| " | ".to_string());
+    let format = current_format(module, table);
+
+    let suffix = if show_percentage { "_pct" } else { "" };
+    let mut variables = format!("$ram{}", suffix);
+    if show_swap {
+        variables.push_str(&format!(
+            "({}$swap{})",
+            escape_format_text(&separator),
+            suffix
+        ));
+    }
+    let format = replace_variable(&format, "ram", &variables);
+    table.insert("format", toml_edit::value(format));
+}
+
+/// `prompt_order` listed the modules of the prompt, which are now the variables of `format`
+fn migrate_prompt_order(_module: &str, table: &mut dyn TableLike) {
+    let prompt_order = match table.remove("prompt_order") {
+        Some(prompt_order) => prompt_order,
+        None => return,
+    };
+    let modules = prompt_order.as_array().into_iter().flatten();
+    let format: String = modules
+        .filter_map(|module| module.as_str())
+        .map(|module| {
+            if module
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_')
+            {
+                format!("${}", module)
+            } else {
+                format!("${{{}}}", module)
+            }
+        })
+        .collect();
+    table.insert("format", toml_edit::value(format));
+}
+
+/// `show_sync_count` and the `<status>_count` tables added the counts after the symbols of
+/// `git_status`, which now use a `$count` variable
+fn migrate_git_status(module: &str, table: &mut dyn TableLike) {
+    if remove_bool(table, "show_sync_count").unwrap_or(false) {
+        for key in &["ahead", "behind"] {
+            let symbol = current_option(module, table, key);
+            table.insert(key, toml_edit::value(format!("{}${{count}}", symbol)));
+        }
+        let diverged = current_option(module, table, "diverged");
+        let ahead = current_option(module, table, "ahead");
+        let behind = current_option(module, table, "behind");
+        let diverged = format!(
+            "{}{}${{ahead_count}}{}${{behind_count}}",
+            diverged,
+            ahead.trim_end_matches("${count}"),
+            behind.trim_end_matches("${count}")
+        );
+        table.insert("diverged", toml_edit::value(diverged));
+    }
+
+    for status in GIT_STATUS_COUNTS {
+        let count = match table.remove(&format!("{}_count", status)) {
+            Some(count) => count,
+            None => continue,
+        };
+        let (enabled, style) = match count.as_table_like() {
+            Some(count) => (
+                count
+                    .get("enabled")
+                    .and_then(Item::as_bool)
+                    .unwrap_or(false),
+                count.get("style").and_then(Item::as_str).map(String::from),
+            ),
+            None => (count.as_bool().unwrap_or(false), None),
+        };
+        if enabled {
+            let symbol = current_option(module, table, status);
+            let symbol = match style {
+                Some(style) => format!("{}[${{count}}]({})", symbol, style),
+                None => format!("{}${{count}}", symbol),
+            };
+            table.insert(status, toml_edit::value(symbol));
+        }
+    }
+}
+
+/// `use_name` showed the name of the nix shell after its state
+fn migrate_nix_shell(module: &str, table: &mut dyn TableLike) {
+    if remove_bool(table, "use_name").unwrap_or(false) {
+        let format = current_format(module, table);
+        let format = replace_variable(&format, "state", r"$state( \($name\))");
+        table.insert("format", toml_edit::value(format));
+    }
+}
+
+/// `show_version` showed the version of terraform before the workspace
+fn migrate_terraform(module: &str, table: &mut dyn TableLike) {
+    if remove_bool(table, "show_version").unwrap_or(false) {
+        let format = current_format(module, table);
+        let format = replace_variable(&format, "workspace", "$version $workspace");
+        table.insert("format", toml_edit::value(format));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn migrate_str(config: &str) -> String {
+        let mut document = config.parse::<Document>().unwrap();
+        migrate(&mut document);
+        document.to_string()
+    }
+
+    #[test]
+    fn test_split_format() {
+        assert_eq!(
+            split_format("via [$symbol$version]($style) "),
+            ("via ", "[$symbol$version]($style)", " ")
+        );
+        assert_eq!(split_format("$symbol "), ("", "$symbol", " "));
+        assert_eq!(
+            split_format("\\[${custom} \\]"),
+            ("\\[", "${custom}", " \\]")
+        );
+        assert_eq!(split_format("plain"), ("plain", "", ""));
+    }
+
+    #[test]
+    fn test_find_deprecated() {
+        let config = toml::toml! {
+            [cmd_duration]
+            prefix = "took "
+            [character]
+            use_symbol_for_status = true
+            [custom.foo]
+            suffix = ""
+            [theme.dark.memory_usage]
+            show_percentage = true
+            [palettes.foo]
+            prefix = "red"
+            [[when]]
+            shell = "bash"
+            [when.cmd_duration]
+            suffix = ""
+        };
+        let keys: Vec<String> = find_deprecated(&config)
+            .iter()
+            .map(Deprecation::key)
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                "cmd_duration.prefix",
+                "character.use_symbol_for_status",
+                "custom.foo.suffix",
+                "theme.dark.memory_usage.show_percentage",
+                "when.0.cmd_duration.suffix",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_migrate_prefix_suffix() {
+        let config = r#"# Modules
+[cmd_duration]
+prefix = "underwent "
+min_time = 500 # milliseconds
+
+[custom.foo]
+command = "echo foo"
+suffix = " ] "
+"#;
+        let expected = r#"# Modules
+[cmd_duration]
+min_time = 500 # milliseconds
+format = "underwent [$duration]($style) "
+
+[custom.foo]
+command = "echo foo"
+format = '[$symbol$output]($style) \] '
+"#;
+        assert_eq!(migrate_str(config), expected);
+    }
+
+    #[test]
+    fn test_migrate_character() {
+        let config = r#"[character]
+symbol = "➜"
+error_symbol = "✗"
+use_symbol_for_status = true
+style_failure = "red"
+"#;
+        let expected = r#"[character]
+error_symbol = "[✗](red)"
+success_symbol = "[➜](bold green)"
+"#;
+        assert_eq!(migrate_str(config), expected);
+
+        let config = "[character]\nsymbol = \"$\"\n";
+        let expected =
+            "[character]\nsuccess_symbol = '[\\$](bold green)'\nerror_symbol = '[\\$](bold red)'\n";
+        assert_eq!(migrate_str(config), expected);
+    }
+
+    #[test]
+    fn test_migrate_memory_usage() {
+        let config = "[memory_usage]\ndisabled = false\nshow_percentage = true\n";
+        let expected = "[memory_usage]\ndisabled = false\nformat = \"via [$symbol$ram_pct( | $swap_pct)]($style) \"\n";
+        assert_eq!(migrate_str(config), expected);
+
+        let config = "[memory_usage]\nseparator = \"/\"\n";
+        let expected = "[memory_usage]\nformat = \"via [$symbol$ram(/$swap)]($style) \"\n";
+        assert_eq!(migrate_str(config), expected);
+
+        let config = "[memory_usage]\nshow_swap = false\nprefix = \"\"\n";
+        let expected = "[memory_usage]\nformat = \"[$symbol$ram]($style) \"\n";
+        assert_eq!(migrate_str(config), expected);
+    }
+
+    #[test]
+    fn test_migrate_prompt_order() {
+        let config =
+            "add_newline = false\nprompt_order = [\"directory\", \"custom.foo\", \"character\"]\n";
+        let expected = "add_newline = false\nformat = \"$directory${custom.foo}$character\"\n";
+        assert_eq!(migrate_str(config), expected);
+    }
+
+    #[test]
+    fn test_migrate_git_status() {
+        let config = r#"[git_status]
+show_sync_count = true
+ahead = "A"
+staged_count = { enabled = true, style = "green" }
+modified_count.enabled = false
+untracked_count = true
+"#;
+        let expected = r#"[git_status]
+ahead = "A${count}"
+behind = "⇣${count}"
+diverged = "⇕A${ahead_count}⇣${behind_count}"
+staged = "+[${count}](green)"
+untracked = "?${count}"
+"#;
+        assert_eq!(migrate_str(config), expected);
+    }
+
+    #[test]
+    fn test_migrate_nix_shell_terraform() {
+        let config = "[nix_shell]\nuse_name = true\n[terraform]\nshow_version = true\n";
+        let expected = r#"[nix_shell]
+format = 'via [$symbol$state( \($name\))]($style) '
+[terraform]
+format = "via [$symbol$version $workspace]($style) "
+"#;
+        assert_eq!(migrate_str(config), expected);
+    }
+
+    #[test]
+    fn test_migrate_when_block() {
+        let config = "[[when]]\nshell = \"bash\"\n[when.cmd_duration]\nprefix = \"\"\n";
+        let expected =
+            "[[when]]\nshell = \"bash\"\n[when.cmd_duration]\nformat = \"[$duration]($style) \"\n";
+        assert_eq!(migrate_str(config), expected);
+    }
+
+    #[test]
+    fn test_migrate_current_config() {
+        let config = "[character]\nsuccess_symbol = \"[>](bold green)\"\n";
+        assert_eq!(migrate_str(config), config);
+    }
+}
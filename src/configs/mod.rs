@@ -12,6 +12,7 @@ pub mod elixir;
 pub mod elm;
 pub mod env_var;
 pub mod erlang;
+pub mod fill;
 pub mod git_branch;
 pub mod git_commit;
 pub mod git_state;
@@ -25,6 +26,7 @@ pub mod jobs;
 pub mod julia;
 pub mod kubernetes;
 pub mod memory_usage;
+pub mod migrations;
 pub mod nim;
 pub mod nix_shell;
 pub mod nodejs;
@@ -43,3 +45,492 @@ pub mod username;
 pub mod zig;
 
 pub use starship_root::*;
+
+use crate::config::{
+    self, ConfigError, ConfigErrorKind, ModuleConfig, RootModuleConfig, StarshipConfig,
+};
+use crate::module::ALL_MODULES;
+use crate::modules;
+use crate::presets;
+use toml::Value;
+
+/// Check the whole configuration against the config of each module
+///
+/// Color names in style strings are looked up in the palette of `resolved_config`, the
+/// configuration merged from every layer, since palettes are often defined in another file.
+pub fn validate(config: &Value, resolved_config: &Value) -> Vec<ConfigError> {
+    let mut errors = Vec::new();
+    let table = match config.as_table() {
+        Some(table) => table,
+        None => {
+            errors.push(ConfigError::invalid_value(&[], config));
+            return errors;
+        }
+    };
+
+    // Keys which aren't modules are options of the root config
+    let mut root_config = toml::value::Table::new();
+    for (name, value) in table.iter() {
+        let path = [name.clone()];
+        match name.as_str() {
+            "custom" => match value.as_table() {
+                Some(custom_modules) => {
+                    for (custom_name, custom_config) in custom_modules.iter() {
+                        let path = [name.clone(), custom_name.clone()];
+                        let custom_config = validate_priority(custom_config, &path, &mut errors);
+                        custom::CustomConfig::validate(&custom_config, &path, &mut errors);
+                    }
+                }
+                None => errors.push(ConfigError::invalid_value(&path, value)),
+            },
+            config::THEME_KEY => validate_theme(value, resolved_config, &mut errors),
+            config::WHEN_KEY => validate_when(value, resolved_config, &mut errors),
+            module => {
+                if !validate_module(module, value, &mut errors) {
+                    root_config.insert(name.clone(), value.clone());
+                }
+            }
+        }
+    }
+    StarshipRootConfig::validate(&Value::Table(root_config), &[], &mut errors);
+
+    if let Some(preset) = table.get(config::PRESET_KEY) {
+        if preset.as_str().and_then(presets::get_preset).is_none() {
+            errors.push(ConfigError::invalid_value(
+                &[config::PRESET_KEY.to_string()],
+                preset,
+            ));
+        }
+    }
+
+    let palette = config::get_palette(resolved_config);
+    if let Some(name) = table.get("palette").and_then(Value::as_str) {
+        if palette.is_none() {
+            errors.push(ConfigError::undefined_palette(name));
+        }
+    }
+    errors.retain(|error| match &error.kind {
+        ConfigErrorKind::InvalidStyle(style) => {
+            config::parse_style_string_with_palette(style, palette).is_none()
+        }
+        _ => true,
+    });
+
+    // Keys replaced by a release are reported with what replaced them
+    for deprecation in migrations::find_deprecated(config) {
+        let error = errors.iter_mut().find(|error| {
+            error.path == deprecation.path && error.kind == ConfigErrorKind::UnknownKey
+        });
+        if let Some(error) = error {
+            error.kind = ConfigErrorKind::DeprecatedKey {
+                version: deprecation.migration.version,
+                replacement: deprecation.migration.replacement,
+            };
+        }
+    }
+
+    errors
+}
+
+/// Check the `[[when]]` blocks: their conditions and their overrides, which are checked like
+/// the whole configuration
+fn validate_when(config: &Value, resolved_config: &Value, errors: &mut Vec<ConfigError>) {
+    fn validate_patterns(value: &Value, path: &[String], errors: &mut Vec<ConfigError>) {
+        let is_valid = match value {
+            Value::String(_) => true,
+            Value::Array(patterns) => patterns.iter().all(Value::is_str),
+            _ => false,
+        };
+        if !is_valid {
+            errors.push(ConfigError::invalid_value(path, value));
+        }
+    }
+
+    let when_path = [config::WHEN_KEY.to_string()];
+    let blocks = match config.as_array() {
+        Some(blocks) => blocks,
+        None => {
+            errors.push(ConfigError::invalid_value(&when_path, config));
+            return;
+        }
+    };
+
+    for (index, block) in blocks.iter().enumerate() {
+        let block_path = [config::WHEN_KEY.to_string(), index.to_string()];
+        let table = match block.as_table() {
+            Some(table) => table,
+            None => {
+                errors.push(ConfigError::invalid_value(&block_path, block));
+                continue;
+            }
+        };
+
+        let mut overrides = toml::value::Table::new();
+        for (key, value) in table.iter() {
+            let path = [block_path.to_vec(), vec![key.clone()]].concat();
+            match key.as_str() {
+                key if !config::is_when_condition(key, value) => {
+                    // Blocks aren't applied inside other blocks
+                    if key == config::WHEN_KEY {
+                        errors.push(ConfigError::unknown_key(&path));
+                    } else {
+                        overrides.insert(key.to_string(), value.clone());
+                    }
+                }
+                "env" => match value.as_table() {
+                    Some(vars) => {
+                        for (name, patterns) in vars.iter() {
+                            let var_path = [path.to_vec(), vec![name.clone()]].concat();
+                            validate_patterns(patterns, &var_path, errors);
+                        }
+                    }
+                    None => errors.push(ConfigError::invalid_value(&path, value)),
+                },
+                _ => validate_patterns(value, &path, errors),
+            }
+        }
+
+        let override_errors = validate(&Value::Table(overrides), resolved_config);
+        errors.extend(override_errors.into_iter().map(|error| {
+            ConfigError {
+                path: block_path
+                    .iter()
+                    .chain(error.path.iter())
+                    .cloned()
+                    .collect(),
+                ..error
+            }
+        }));
+    }
+}
+
+/// Check the `[theme]` table: its `mode` and the overrides of each theme, which are
+/// checked like the whole configuration
+fn validate_theme(config: &Value, resolved_config: &Value, errors: &mut Vec<ConfigError>) {
+    let theme_path = [config::THEME_KEY.to_string()];
+    let table = match config.as_table() {
+        Some(table) => table,
+        None => {
+            errors.push(ConfigError::invalid_value(&theme_path, config));
+            return;
+        }
+    };
+
+    for (key, value) in table.iter() {
+        let path = [config::THEME_KEY.to_string(), key.clone()];
+        match key.as_str() {
+            "mode" => match value.as_str() {
+                Some("auto") => {}
+                Some(mode) if config::Theme::from_name(mode).is_some() => {}
+                _ => errors.push(ConfigError::invalid_value(&path, value)),
+            },
+            theme if config::Theme::from_name(theme).is_some() => {
+                errors.extend(validate(value, resolved_config).into_iter().map(|error| {
+                    ConfigError {
+                        path: path.iter().chain(error.path.iter()).cloned().collect(),
+                        ..error
+                    }
+                }))
+            }
+            _ => errors.push(ConfigError::unknown_key(&path)),
+        }
+    }
+}
+
+/// Declares the config struct of each module, generating the functions which need to
+/// dispatch on the name of a module
+macro_rules! module_configs {
+    ($($name:literal => $config:ty,)*) => {
+        /// Check the config of a module, returning `false` if there is no module with that name
+        fn validate_module(module: &str, config: &Value, errors: &mut Vec<ConfigError>) -> bool {
+            let path = [module.to_string()];
+            match module {
+                $($name => {
+                    let config = validate_priority(config, &path, errors);
+                    <$config>::validate(&config, &path, errors)
+                })*
+                "line_break" => validate_disabled_only(config, &path, errors),
+                _ => return false,
+            };
+            true
+        }
+
+        /// The config of a module merged with the default values, if there is such a module
+        fn resolve_module(module: &str, config: Option<&Value>) -> Option<Value> {
+            match module {
+                $($name => <$config>::try_load(config).to_config(),)*
+                "line_break" => {
+                    let disabled = config
+                        .and_then(|config| config.get("disabled"))
+                        .and_then(Value::as_bool)
+                        .unwrap_or(false);
+                    let mut table = toml::value::Table::new();
+                    table.insert("disabled".to_string(), Value::from(disabled));
+                    Some(Value::Table(table))
+                }
+                _ => None,
+            }
+        }
+
+        /// The schemas of the config of every module, with their default values
+        fn module_schemas() -> Vec<(&'static str, serde_json::Value)> {
+            vec![
+                $(($name, with_priority_schema(
+                    schema_with_defaults::<$config>(),
+                    modules::default_priority($name),
+                )),)*
+                ("line_break", disabled_only_schema()),
+            ]
+        }
+    };
+}
+
+module_configs! {
+    "aws" => aws::AwsConfig,
+    "battery" => battery::BatteryConfig,
+    "character" => character::CharacterConfig,
+    "cmd_duration" => cmd_duration::CmdDurationConfig,
+    "conda" => conda::CondaConfig,
+    "crystal" => crystal::CrystalConfig,
+    "directory" => directory::DirectoryConfig,
+    "docker_context" => docker_context::DockerContextConfig,
+    "dotnet" => dotnet::DotnetConfig,
+    "elixir" => elixir::ElixirConfig,
+    "elm" => elm::ElmConfig,
+    "env_var" => env_var::EnvVarConfig,
+    "erlang" => erlang::ErlangConfig,
+    "fill" => fill::FillConfig,
+    "git_branch" => git_branch::GitBranchConfig,
+    "git_commit" => git_commit::GitCommitConfig,
+    "git_state" => git_state::GitStateConfig,
+    "git_status" => git_status::GitStatusConfig,
+    "golang" => go::GoConfig,
+    "haskell" => haskell::HaskellConfig,
+    "hg_branch" => hg_branch::HgBranchConfig,
+    "hostname" => hostname::HostnameConfig,
+    "java" => java::JavaConfig,
+    "jobs" => jobs::JobsConfig,
+    "julia" => julia::JuliaConfig,
+    "kubernetes" => kubernetes::KubernetesConfig,
+    "memory_usage" => memory_usage::MemoryConfig,
+    "nim" => nim::NimConfig,
+    "nix_shell" => nix_shell::NixShellConfig,
+    "nodejs" => nodejs::NodejsConfig,
+    "ocaml" => ocaml::OCamlConfig,
+    "package" => package::PackageConfig,
+    "php" => php::PhpConfig,
+    "purescript" => purescript::PureScriptConfig,
+    "python" => python::PythonConfig,
+    "ruby" => ruby::RubyConfig,
+    "rust" => rust::RustConfig,
+    "singularity" => singularity::SingularityConfig,
+    "terraform" => terraform::TerraformConfig,
+    "time" => time::TimeConfig,
+    "username" => username::UsernameConfig,
+    "zig" => zig::ZigConfig,
+}
+
+/// Check the `priority` option, which every module accepts besides the options of its
+/// config, returning the config without it
+fn validate_priority(config: &Value, path: &[String], errors: &mut Vec<ConfigError>) -> Value {
+    let mut config = config.clone();
+    if let Some(priority) = config
+        .as_table_mut()
+        .and_then(|table| table.remove("priority"))
+    {
+        let mut key_path = path.to_vec();
+        key_path.push("priority".to_string());
+        i64::validate(&priority, &key_path, errors);
+    }
+    config
+}
+
+/// Add the `priority` of a module, from its config or else `default`, to its resolved config
+fn resolve_priority(resolved: &mut Value, config: Option<&Value>, default: i64) {
+    let priority = config
+        .and_then(|config| config.get("priority"))
+        .and_then(Value::as_integer)
+        .unwrap_or(default);
+    if let Some(table) = resolved.as_table_mut() {
+        table.insert("priority".to_string(), Value::from(priority));
+    }
+}
+
+/// Check the config of a module which only supports the `disabled` option
+fn validate_disabled_only(config: &Value, path: &[String], errors: &mut Vec<ConfigError>) {
+    match config.as_table() {
+        Some(table) => {
+            for (key, value) in table.iter() {
+                let mut key_path = path.to_vec();
+                key_path.push(key.clone());
+                match key.as_str() {
+                    "disabled" => bool::validate(value, &key_path, errors),
+                    _ => errors.push(ConfigError::unknown_key(&key_path)),
+                }
+            }
+        }
+        None => errors.push(ConfigError::invalid_value(path, config)),
+    }
+}
+
+/// Merge the user configuration with the default values of the root config, every module
+/// and every custom module
+///
+/// Returns a list of tables with the path to each table, the root config having an empty path.
+pub fn resolve(config: &StarshipConfig) -> Vec<(Vec<String>, Value)> {
+    let mut tables = Vec::new();
+    if let Some(root_config) = config.get_root_config().to_config() {
+        tables.push((Vec::new(), root_config));
+    }
+
+    for module in ALL_MODULES {
+        let module_config = config.get_module_config(module);
+        if let Some(mut resolved) = resolve_module(module, module_config) {
+            if *module != "line_break" {
+                resolve_priority(
+                    &mut resolved,
+                    module_config,
+                    modules::default_priority(module),
+                );
+            }
+            tables.push((vec![module.to_string()], resolved));
+        }
+    }
+
+    if let Some(custom_modules) = config.get_custom_modules() {
+        for (name, custom_config) in custom_modules.iter() {
+            if let Some(mut resolved) = custom::CustomConfig::load(custom_config).to_config() {
+                resolve_priority(
+                    &mut resolved,
+                    Some(custom_config),
+                    modules::DEFAULT_PRIORITY,
+                );
+                tables.push((vec!["custom".to_string(), name.clone()], resolved));
+            }
+        }
+    }
+
+    tables
+}
+
+/// Generate a JSON Schema for the whole configuration file
+pub fn schema() -> serde_json::Value {
+    let mut root_schema = schema_with_defaults::<StarshipRootConfig>();
+    let properties = root_schema["properties"]
+        .as_object_mut()
+        .expect("the root config is a table");
+
+    for (name, module_schema) in module_schemas() {
+        properties.insert(name.to_string(), module_schema);
+    }
+    properties.insert(
+        "custom".to_string(),
+        serde_json::json!({
+            "type": "object",
+            "additionalProperties": with_priority_schema(
+                schema_with_defaults::<custom::CustomConfig>(),
+                modules::DEFAULT_PRIORITY,
+            ),
+        }),
+    );
+    properties.insert(
+        config::THEME_KEY.to_string(),
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "mode": { "type": "string", "enum": ["auto", "light", "dark"], "default": "auto" },
+                // The overrides of each theme accept the whole configuration
+                "light": { "$ref": "#" },
+                "dark": { "$ref": "#" },
+            },
+            "additionalProperties": false,
+        }),
+    );
+
+    // The overrides of a `[[when]]` block accept every option of the whole configuration
+    let mut when_properties: serde_json::Map<String, serde_json::Value> = properties
+        .keys()
+        .map(|name| {
+            let reference = format!("#/properties/{}", name);
+            (name.clone(), serde_json::json!({ "$ref": reference }))
+        })
+        .collect();
+    let patterns = serde_json::json!({
+        "oneOf": [
+            { "type": "string" },
+            { "type": "array", "items": { "type": "string" } },
+        ],
+    });
+    for condition in config::WHEN_CONDITIONS {
+        // A condition named after a module can also configure that module
+        let schema = match when_properties.remove(*condition) {
+            Some(module) => serde_json::json!({ "oneOf": [patterns, module] }),
+            None => patterns.clone(),
+        };
+        when_properties.insert(condition.to_string(), schema);
+    }
+    when_properties.insert(
+        "env".to_string(),
+        serde_json::json!({ "type": "object", "additionalProperties": patterns }),
+    );
+    properties.insert(
+        config::WHEN_KEY.to_string(),
+        serde_json::json!({
+            "type": "array",
+            "items": {
+                "type": "object",
+                "properties": when_properties,
+                "additionalProperties": false,
+            },
+        }),
+    );
+
+    root_schema["$schema"] = "http://json-schema.org/draft-07/schema#".into();
+    root_schema["title"] = "Starship configuration".into();
+    root_schema
+}
+
+fn schema_with_defaults<'a, T: RootModuleConfig<'a>>() -> serde_json::Value {
+    let mut schema = T::schema();
+    if let Some(defaults) = T::new().to_config() {
+        add_defaults(&mut schema, &defaults);
+    }
+    schema
+}
+
+/// Recursively set the `default` of each property of `schema` from a toml value
+fn add_defaults(schema: &mut serde_json::Value, defaults: &Value) {
+    match (schema.get_mut("properties"), defaults) {
+        (Some(serde_json::Value::Object(properties)), Value::Table(table)) => {
+            for (key, value) in table.iter() {
+                if let Some(property) = properties.get_mut(key) {
+                    add_defaults(property, value);
+                }
+            }
+        }
+        _ => {
+            if let Ok(default) = serde_json::to_value(defaults) {
+                schema["default"] = default;
+            }
+        }
+    }
+}
+
+/// Add the `priority` option, which every module accepts, to the schema of a module
+fn with_priority_schema(mut schema: serde_json::Value, default: i64) -> serde_json::Value {
+    if let Some(properties) = schema["properties"].as_object_mut() {
+        properties.insert(
+            "priority".to_string(),
+            serde_json::json!({ "type": "integer", "default": default }),
+        );
+    }
+    schema
+}
+
+fn disabled_only_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": { "disabled": { "type": "boolean", "default": false } },
+        "additionalProperties": false,
+    })
+}
@@ -1,12 +1,12 @@
-use crate::config::{ModuleConfig, RootModuleConfig, SegmentConfig};
+use crate::config::{ModuleConfig, RootModuleConfig};
 
-use ansi_term::{Color, Style};
 use starship_module_config_derive::ModuleConfig;
 
 #[derive(Clone, ModuleConfig)]
 pub struct PackageConfig<'a> {
-    pub symbol: SegmentConfig<'a>,
-    pub style: Style,
+    pub format: &'a str,
+    pub symbol: &'a str,
+    pub style: &'a str,
     pub display_private: bool,
     pub disabled: bool,
 }
@@ -14,8 +14,9 @@ pub struct PackageConfig<'a> {
 impl<'a> RootModuleConfig<'a> for PackageConfig<'a> {
     fn new() -> Self {
         PackageConfig {
-            symbol: SegmentConfig::new("📦 "),
-            style: Color::Fixed(208).bold(),
+            format: "is [$symbol$version]($style) ",
+            symbol: "📦 ",
+            style: "208 bold",
             display_private: false,
             disabled: false,
         }
@@ -1,22 +1,21 @@
-use crate::config::{ModuleConfig, RootModuleConfig, SegmentConfig};
+use crate::config::{ModuleConfig, RootModuleConfig};
 
-use ansi_term::{Color, Style};
 use starship_module_config_derive::ModuleConfig;
 
 #[derive(Clone, ModuleConfig)]
 pub struct PureScriptConfig<'a> {
-    pub symbol: SegmentConfig<'a>,
-    pub version: SegmentConfig<'a>,
-    pub style: Style,
+    pub format: &'a str,
+    pub symbol: &'a str,
+    pub style: &'a str,
     pub disabled: bool,
 }
 
 impl<'a> RootModuleConfig<'a> for PureScriptConfig<'a> {
     fn new() -> Self {
         PureScriptConfig {
-            symbol: SegmentConfig::new("<=> "),
-            version: SegmentConfig::default(),
-            style: Color::White.bold(),
+            format: "via [$symbol$version]($style) ",
+            symbol: "<=> ",
+            style: "bold white",
             disabled: false,
         }
     }
@@ -1,26 +1,21 @@
-use crate::config::{ModuleConfig, RootModuleConfig, SegmentConfig};
+use crate::config::{ModuleConfig, RootModuleConfig};
 
-use ansi_term::{Color, Style};
 use starship_module_config_derive::ModuleConfig;
 
 #[derive(Clone, ModuleConfig)]
 pub struct SingularityConfig<'a> {
-    pub symbol: SegmentConfig<'a>,
-    pub label: &'a str,
-    pub prefix: &'a str,
-    pub suffix: &'a str,
-    pub style: Style,
+    pub symbol: &'a str,
+    pub format: &'a str,
+    pub style: &'a str,
     pub disabled: bool,
 }
 
 impl<'a> RootModuleConfig<'a> for SingularityConfig<'a> {
     fn new() -> Self {
         SingularityConfig {
-            symbol: SegmentConfig::default(),
-            label: "",
-            prefix: "[",
-            suffix: "]",
-            style: Color::Blue.bold().dimmed(),
+            format: "[$symbol\\[$env\\]]($style) ",
+            symbol: "",
+            style: "blue bold dimmed",
             disabled: false,
         }
     }
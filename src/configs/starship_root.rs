@@ -1,71 +1,104 @@
-use crate::config::{ModuleConfig, RootModuleConfig};
+use crate::config::{ColorDepth, ModuleConfig, RootModuleConfig, SymbolSet, VecOr};
 
 use starship_module_config_derive::ModuleConfig;
+use std::collections::HashMap;
+
+// List of default prompt order
+// NOTE: If this const value is changed then Default prompt order subheading inside
+// prompt heading of config docs needs to be updated according to changes made here.
+pub const PROMPT_ORDER: &[&str] = &[
+    "username",
+    "hostname",
+    "singularity",
+    "kubernetes",
+    "directory",
+    "git_branch",
+    "git_commit",
+    "git_state",
+    "git_status",
+    "hg_branch",
+    "docker_context",
+    "package",
+    // ↓ Toolchain version modules ↓
+    // (Let's keep these sorted alphabetically)
+    "dotnet",
+    "elixir",
+    "elm",
+    "erlang",
+    "golang",
+    "haskell",
+    "java",
+    "julia",
+    "nim",
+    "nodejs",
+    "ocaml",
+    "php",
+    "purescript",
+    "python",
+    "ruby",
+    "rust",
+    "terraform",
+    "zig",
+    // ↑ Toolchain version modules ↑
+    "nix_shell",
+    "conda",
+    "memory_usage",
+    "aws",
+    "env_var",
+    "crystal",
+    "cmd_duration",
+    "custom",
+    "line_break",
+    "jobs",
+    #[cfg(feature = "battery")]
+    "battery",
+    "time",
+    "character",
+];
 
 #[derive(Clone, ModuleConfig)]
 pub struct StarshipRootConfig<'a> {
+    pub format: &'a str,
+    pub right_format: &'a str,
+    pub transient_format: &'a str,
+    pub transient_right_format: &'a str,
+    pub continuation_prompt: &'a str,
     pub add_newline: bool,
-    pub prompt_order: Vec<&'a str>,
     pub scan_timeout: u64,
+    pub include: VecOr<&'a str>,
+    pub trusted_projects: Vec<&'a str>,
+    pub preset: Option<&'a str>,
+    pub symbols: SymbolSet,
+    pub palette: Option<&'a str>,
+    pub palettes: HashMap<String, HashMap<String, &'a str>>,
+    pub color_depth: Option<ColorDepth>,
+    pub redact: bool,
+    pub powerline: bool,
+    pub powerline_separator: &'a str,
+    pub powerline_right_separator: &'a str,
 }
 
 impl<'a> RootModuleConfig<'a> for StarshipRootConfig<'a> {
     fn new() -> Self {
         StarshipRootConfig {
+            format: "$all",
+            right_format: "",
+            transient_format: "$character",
+            transient_right_format: "",
+            continuation_prompt: "[∙](bright-black) ",
             add_newline: true,
-            // List of default prompt order
-            // NOTE: If this const value is changed then Default prompt order subheading inside
-            // prompt heading of config docs needs to be updated according to changes made here.
-            prompt_order: vec![
-                "username",
-                "hostname",
-                "singularity",
-                "kubernetes",
-                "directory",
-                "git_branch",
-                "git_commit",
-                "git_state",
-                "git_status",
-                "hg_branch",
-                "docker_context",
-                "package",
-                // ↓ Toolchain version modules ↓
-                // (Let's keep these sorted alphabetically)
-                "dotnet",
-                "elixir",
-                "elm",
-                "erlang",
-                "golang",
-                "haskell",
-                "java",
-                "julia",
-                "nim",
-                "nodejs",
-                "ocaml",
-                "php",
-                "purescript",
-                "python",
-                "ruby",
-                "rust",
-                "terraform",
-                "zig",
-                // ↑ Toolchain version modules ↑
-                "nix_shell",
-                "conda",
-                "memory_usage",
-                "aws",
-                "env_var",
-                "crystal",
-                "cmd_duration",
-                "custom",
-                "line_break",
-                "jobs",
-                #[cfg(feature = "battery")]
-                "battery",
-                "time",
-                "character",
-            ],
             scan_timeout: 30,
+            include: VecOr::default(),
+            trusted_projects: Vec::new(),
+            preset: None,
+            symbols: SymbolSet::Unicode,
+            palette: None,
+            palettes: HashMap::new(),
+            color_depth: None,
+            redact: false,
+            powerline: false,
+            powerline_separator: "\u{e0b0}",
+            powerline_right_separator: "\u{e0b2}",
         }
     }
 }
@@ -1,26 +1,21 @@
-use crate::config::{ModuleConfig, RootModuleConfig, SegmentConfig};
+use crate::config::{ModuleConfig, RootModuleConfig};
 
-use ansi_term::{Color, Style};
 use starship_module_config_derive::ModuleConfig;
 
 #[derive(Clone, ModuleConfig)]
 pub struct TerraformConfig<'a> {
-    pub symbol: SegmentConfig<'a>,
-    pub workspace: SegmentConfig<'a>,
-    pub version: SegmentConfig<'a>,
-    pub show_version: bool,
-    pub style: Style,
+    pub format: &'a str,
+    pub symbol: &'a str,
+    pub style: &'a str,
     pub disabled: bool,
 }
 
 impl<'a> RootModuleConfig<'a> for TerraformConfig<'a> {
     fn new() -> Self {
         TerraformConfig {
-            symbol: SegmentConfig::new("💠 "),
-            workspace: SegmentConfig::default(),
-            version: SegmentConfig::default(),
-            show_version: false,
-            style: Color::Fixed(105).bold(),
+            format: "via [$symbol$workspace]($style) ",
+            symbol: "💠 ",
+            style: "bold 105",
             disabled: false,
         }
     }
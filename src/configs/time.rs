@@ -1,13 +1,13 @@
 use crate::config::{ModuleConfig, RootModuleConfig};
 
-use ansi_term::{Color, Style};
 use starship_module_config_derive::ModuleConfig;
 
 #[derive(Clone, ModuleConfig)]
 pub struct TimeConfig<'a> {
+    pub format: &'a str,
+    pub style: &'a str,
     pub use_12hr: bool,
-    pub format: Option<&'a str>,
-    pub style: Style,
+    pub time_format: Option<&'a str>,
     pub disabled: bool,
     pub utc_time_offset: &'a str,
     pub time_range: &'a str,
@@ -16,9 +16,10 @@ pub struct TimeConfig<'a> {
 impl<'a> RootModuleConfig<'a> for TimeConfig<'a> {
     fn new() -> Self {
         TimeConfig {
+            format: "at [$time]($style) ",
+            style: "bold yellow",
             use_12hr: false,
-            format: None,
-            style: Color::Yellow.bold(),
+            time_format: None,
             disabled: true,
             utc_time_offset: "local",
             time_range: "-",
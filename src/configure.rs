@@ -1,61 +1,536 @@
 use std::env;
 use std::ffi::OsString;
-use std::io::ErrorKind;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
 use std::process;
 use std::process::Command;
 
-use starship::config::StarshipConfig;
-use std::fs::File;
-use std::io::Write;
+use crate::config::{self, ConfigError, ConfigLayer, ConfigSource, StarshipConfig};
+use crate::configs::{self, migrations};
+use crate::presets;
+use std::fs;
 use toml::map::Map;
 use toml::Value;
+use toml_edit::{Array, ArrayOfTables, Document, InlineTable, Item, Table};
 
 const STD_EDITOR: &str = "vi";
 
-pub fn update_configuration(name: &str, value: &str) {
-    let config_path = get_config_path();
+/// A segment of a key path such as `battery.display[1].style`
+#[derive(Debug, PartialEq)]
+enum KeySegment {
+    Key(String),
+    Index(usize),
+}
+
+/// Parse a key path made of dotted keys, quoted keys (`custom."my.module"`) and array indices
+/// (`display[0]`)
+fn parse_key_path(path: &str) -> Option<Vec<KeySegment>> {
+    let mut segments = Vec::new();
+    let mut chars = path.chars().peekable();
+    let mut expect_key = true;
+
+    loop {
+        if expect_key {
+            let mut key = String::new();
+            if chars.peek() == Some(&'"') {
+                chars.next();
+                loop {
+                    match chars.next()? {
+                        '"' => break,
+                        c => key.push(c),
+                    }
+                }
+            } else {
+                while let Some(&c) = chars.peek() {
+                    if !(c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+                        break;
+                    }
+                    key.push(c);
+                    chars.next();
+                }
+                if key.is_empty() {
+                    return None;
+                }
+            }
+            segments.push(KeySegment::Key(key));
+        }
+
+        match chars.next() {
+            None => return Some(segments),
+            Some('.') => expect_key = true,
+            Some('[') => {
+                let mut index = String::new();
+                loop {
+                    match chars.next()? {
+                        ']' => break,
+                        c => index.push(c),
+                    }
+                }
+                segments.push(KeySegment::Index(index.parse().ok()?));
+                expect_key = false;
+            }
+            Some(_) => return None,
+        }
+    }
+}
+
+/// Append a key to a key path, quoting it when it isn't a bare key
+fn join_key_path(prefix: &str, key: &str) -> String {
+    let is_bare = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    let key = if is_bare {
+        key.to_string()
+    } else {
+        toml_edit::Value::from(key).to_string()
+    };
+
+    if prefix.is_empty() {
+        key
+    } else {
+        format!("{}.{}", prefix, key)
+    }
+}
+
+fn format_key_path(path: &[KeySegment]) -> String {
+    path.iter()
+        .fold(String::new(), |prefix, segment| match segment {
+            KeySegment::Key(key) => join_key_path(&prefix, key),
+            KeySegment::Index(index) => format!("{}[{}]", prefix, index),
+        })
+}
+
+/// Parse a value given on the command line as a TOML value (e.g. `true`, `3`, `["a", "b"]` or
+/// `{ style = "red" }`), using it as a string otherwise
+fn parse_edit_value(value: &str) -> toml_edit::Value {
+    value
+        .parse::<toml_edit::Value>()
+        .unwrap_or_else(|_| toml_edit::Value::from(value))
+}
+
+/// Format a value the way it's written in a configuration file, without its whitespace and
+/// comments
+fn format_edit_value(value: &toml_edit::Value) -> String {
+    let mut value = value.clone();
+    value.decor_mut().clear();
+    value.to_string()
+}
+
+fn get_item<'a>(document: &'a Document, path: &[KeySegment]) -> Option<&'a Item> {
+    path.iter()
+        .try_fold(document.as_item(), |item, segment| match segment {
+            KeySegment::Key(key) => item.get(key.as_str()),
+            KeySegment::Index(index) => item.get(*index),
+        })
+}
+
+/// Set the value at `path`, creating the missing tables on the way
+///
+/// An existing value keeps its surrounding whitespace and comments, and an index one past the
+/// end of an array appends to it.
+fn set_item(
+    document: &mut Document,
+    path: &[KeySegment],
+    mut value: toml_edit::Value,
+) -> Result<(), String> {
+    let (last, parents) = path.split_last().ok_or("the key path is empty")?;
+
+    let mut item = document.as_item_mut();
+    for (depth, segment) in parents.iter().enumerate() {
+        // A missing key becomes a table, or an array when it's followed by an index
+        let next_is_index = matches!(path[depth + 1], KeySegment::Index(_));
+        let holds_tables = depth + 2 < path.len() || value.is_inline_table();
+        item = match segment {
+            KeySegment::Key(key) => {
+                match item {
+                    Item::Table(table) if !table.contains_key(key) => {
+                        let new_item = if !next_is_index {
+                            let mut new_table = Table::new();
+                            new_table.set_implicit(true);
+                            Item::Table(new_table)
+                        } else if holds_tables {
+                            Item::ArrayOfTables(ArrayOfTables::new())
+                        } else {
+                            Item::Value(Array::new().into())
+                        };
+                        table.insert(key, new_item);
+                    }
+                    Item::Value(toml_edit::Value::InlineTable(table))
+                        if !table.contains_key(key) =>
+                    {
+                        let new_value = if next_is_index {
+                            Array::new().into()
+                        } else {
+                            InlineTable::new().into()
+                        };
+                        table.insert(key, new_value);
+                    }
+                    _ => {}
+                }
+                item.get_mut(key.as_str()).ok_or_else(|| {
+                    format!("`{}` is not a table", format_key_path(&path[..depth]))
+                })?
+            }
+            KeySegment::Index(index) => {
+                // An index one past the end appends a table to the array
+                match item {
+                    Item::ArrayOfTables(tables) if *index == tables.len() => {
+                        tables.push(Table::new());
+                    }
+                    Item::Value(toml_edit::Value::Array(array)) if *index == array.len() => {
+                        array.push(InlineTable::new());
+                    }
+                    _ => {}
+                }
+                item.get_mut(*index).ok_or_else(|| {
+                    format!("`{}` doesn't exist", format_key_path(&path[..=depth]))
+                })?
+            }
+        };
+    }
+
+    let parent_path = format_key_path(parents);
+    match last {
+        KeySegment::Key(key) => match item {
+            Item::Table(table) => match table.get_mut(key) {
+                Some(Item::Value(old_value)) => {
+                    *value.decor_mut() = old_value.decor().clone();
+                    *old_value = value;
+                }
+                Some(old_item) => *old_item = Item::Value(value),
+                None => {
+                    table.insert(key, Item::Value(value));
+                }
+            },
+            Item::Value(toml_edit::Value::InlineTable(table)) => match table.get_mut(key) {
+                Some(old_value) => {
+                    *value.decor_mut() = old_value.decor().clone();
+                    *old_value = value;
+                }
+                None => {
+                    table.insert(key, value);
+                }
+            },
+            _ => return Err(format!("`{}` is not a table", parent_path)),
+        },
+        KeySegment::Index(index) => {
+            let out_of_range = || format!("`{}[{}]` is out of range", parent_path, index);
+            if let Some(array) = item.as_array_mut() {
+                if *index < array.len() {
+                    array.replace(*index, value);
+                } else if *index == array.len() {
+                    array.push(value);
+                } else {
+                    return Err(out_of_range());
+                }
+            } else if let Some(tables) = item.as_array_of_tables_mut() {
+                let table = match value {
+                    toml_edit::Value::InlineTable(table) => table.into_table(),
+                    _ => return Err(format!("`{}` only holds tables", parent_path)),
+                };
+                if let Some(old_table) = tables.get_mut(*index) {
+                    *old_table = table;
+                } else if *index == tables.len() {
+                    tables.push(table);
+                } else {
+                    return Err(out_of_range());
+                }
+            } else {
+                return Err(format!("`{}` is not an array", parent_path));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Remove the value at `path`, returning whether it was set
+fn unset_item(document: &mut Document, path: &[KeySegment]) -> bool {
+    let (last, parents) = match path.split_last() {
+        Some(split) => split,
+        None => return false,
+    };
+    let item = parents
+        .iter()
+        .try_fold(document.as_item_mut(), |item, segment| match segment {
+            KeySegment::Key(key) => item.get_mut(key.as_str()),
+            KeySegment::Index(index) => item.get_mut(*index),
+        });
+
+    match (item, last) {
+        (Some(Item::Table(table)), KeySegment::Key(key)) => table.remove(key).is_some(),
+        (Some(Item::Value(toml_edit::Value::InlineTable(table))), KeySegment::Key(key)) => {
+            table.remove(key).is_some()
+        }
+        (Some(Item::Value(toml_edit::Value::Array(array))), KeySegment::Index(index))
+            if *index < array.len() =>
+        {
+            let removed = array.remove(*index);
+            // The new first value takes the whitespace after the opening bracket
+            if let (0, Some(first)) = (*index, array.get_mut(0)) {
+                let prefix = removed.decor().prefix().cloned().unwrap_or_default();
+                first.decor_mut().set_prefix(prefix);
+            }
+            true
+        }
+        (Some(Item::ArrayOfTables(tables)), KeySegment::Index(index)) if *index < tables.len() => {
+            tables.remove(*index);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// List every value under `item` as `key.path = value` lines
+fn list_item(item: &Item, prefix: &str, lines: &mut Vec<String>) {
+    match item {
+        Item::None => {}
+        Item::Value(value) => list_value(value, prefix, lines),
+        Item::Table(table) => {
+            for (key, item) in table.iter() {
+                list_item(item, &join_key_path(prefix, key), lines);
+            }
+        }
+        Item::ArrayOfTables(tables) => {
+            for (index, table) in tables.iter().enumerate() {
+                let prefix = format!("{}[{}]", prefix, index);
+                for (key, item) in table.iter() {
+                    list_item(item, &join_key_path(&prefix, key), lines);
+                }
+            }
+        }
+    }
+}
 
-    let keys: Vec<&str> = name.split('.').collect();
-    if keys.len() != 2 {
-        log::error!("Please pass in a config key with a '.'");
+fn list_value(value: &toml_edit::Value, prefix: &str, lines: &mut Vec<String>) {
+    match value {
+        toml_edit::Value::InlineTable(table) => {
+            for (key, value) in table.iter() {
+                list_value(value, &join_key_path(prefix, key), lines);
+            }
+        }
+        value => lines.push(format!("{} = {}", prefix, format_edit_value(value))),
+    }
+}
+
+fn parse_key_path_or_exit(key: &str) -> Vec<KeySegment> {
+    parse_key_path(key).unwrap_or_else(|| {
+        eprintln!("Invalid key path `{}`", key);
+        process::exit(1);
+    })
+}
+
+/// Read the configuration file as an editable document, or an empty one if it doesn't exist
+fn read_config_document(config_path: &Path) -> Document {
+    let content = match fs::read_to_string(config_path) {
+        Ok(content) => content,
+        Err(error) if error.kind() == ErrorKind::NotFound => String::new(),
+        Err(error) => {
+            eprintln!(
+                "Unable to read {}: {}",
+                config_path.to_string_lossy(),
+                error
+            );
+            process::exit(1);
+        }
+    };
+    content.parse::<Document>().unwrap_or_else(|error| {
+        eprintln!(
+            "Unable to parse {}: {}",
+            config_path.to_string_lossy(),
+            error
+        );
         process::exit(1);
+    })
+}
+
+/// Replace the configuration file through a temporary file, so that it's never left
+/// half-written, keeping its previous version in the first free `<file>.bak`, `<file>.bak.1`,
+/// ... path, which is returned
+fn write_config_document(config_path: &Path, document: &Document) -> io::Result<Option<PathBuf>> {
+    // Write through symlinks, e.g. to a configuration kept in a dotfiles repository
+    let config_path = fs::canonicalize(config_path).unwrap_or_else(|_| config_path.into());
+    if let Some(config_dir) = config_path.parent() {
+        fs::create_dir_all(config_dir)?;
     }
 
-    let starship_config = StarshipConfig::initialize();
-    let mut config = starship_config
-        .config
-        .expect("Failed to load starship config");
+    let mut temp_path = config_path.clone().into_os_string();
+    temp_path.push(".tmp");
+    let temp_path = PathBuf::from(temp_path);
+    fs::write(&temp_path, document.to_string())?;
 
-    if let Some(table) = config.as_table_mut() {
-        if !table.contains_key(keys[0]) {
-            table.insert(keys[0].to_string(), Value::Table(Map::new()));
+    let mut backup_path = None;
+    if let Ok(metadata) = fs::metadata(&config_path) {
+        // Earlier backups are never overwritten, the first one holding the original file
+        let path = (0..)
+            .map(|index| {
+                let mut path = config_path.clone().into_os_string();
+                path.push(".bak");
+                if index > 0 {
+                    path.push(format!(".{}", index));
+                }
+                PathBuf::from(path)
+            })
+            .find(|path| !path.exists())
+            .expect("there are infinitely many backup paths");
+        fs::copy(&config_path, &path)?;
+        fs::set_permissions(&temp_path, metadata.permissions())?;
+        backup_path = Some(path);
+    }
+
+    if let Err(error) = fs::rename(&temp_path, &config_path) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(error);
+    }
+    Ok(backup_path)
+}
+
+/// Write the configuration file, exiting on errors, and return the path of its backup
+fn save_config_document(config_path: &Path, document: &Document) -> Option<PathBuf> {
+    match write_config_document(config_path, document) {
+        Ok(backup_path) => backup_path,
+        Err(error) => {
+            eprintln!(
+                "Unable to write {}: {}",
+                config_path.to_string_lossy(),
+                error
+            );
+            process::exit(1);
         }
+    }
+}
 
-        if let Some(values) = table.get(keys[0]).unwrap().as_table() {
-            let mut updated_values = values.clone();
+/// Print the value of a key of the configuration file, or every value under a table
+pub fn get_configuration(key: &str) {
+    let path = parse_key_path_or_exit(key);
+    let document = read_config_document(Path::new(&get_config_path()));
 
-            if value.parse::<bool>().is_ok() {
-                updated_values.insert(
-                    keys[1].to_string(),
-                    Value::Boolean(value.parse::<bool>().unwrap()),
-                );
-            } else if value.parse::<i64>().is_ok() {
-                updated_values.insert(
-                    keys[1].to_string(),
-                    Value::Integer(value.parse::<i64>().unwrap()),
-                );
-            } else {
-                updated_values.insert(keys[1].to_string(), Value::String(value.to_string()));
+    match get_item(&document, &path) {
+        Some(Item::Value(value)) if value.is_str() => println!("{}", value.as_str().unwrap()),
+        Some(Item::Value(value)) if !value.is_inline_table() => {
+            println!("{}", format_edit_value(value))
+        }
+        Some(item) if !item.is_none() => {
+            let mut lines = Vec::new();
+            list_item(item, &format_key_path(&path), &mut lines);
+            for line in lines {
+                println!("{}", line);
             }
+        }
+        _ => {
+            eprintln!("`{}` is not set", key);
+            process::exit(1);
+        }
+    }
+}
+
+/// Set a key of the configuration file, keeping the rest of the file as it is
+pub fn set_configuration(key: &str, value: &str) {
+    let path = parse_key_path_or_exit(key);
+    let config_path = PathBuf::from(get_config_path());
+    let mut document = read_config_document(&config_path);
+
+    if let Err(error) = set_item(&mut document, &path, parse_edit_value(value)) {
+        eprintln!("Unable to set `{}`: {}", key, error);
+        process::exit(1);
+    }
+    save_config_document(&config_path, &document);
+}
+
+/// Remove a key from the configuration file, keeping the rest of the file as it is
+pub fn unset_configuration(key: &str) {
+    let path = parse_key_path_or_exit(key);
+    let config_path = PathBuf::from(get_config_path());
+    let mut document = read_config_document(&config_path);
+
+    if !unset_item(&mut document, &path) {
+        eprintln!("`{}` is not set", key);
+        process::exit(1);
+    }
+    save_config_document(&config_path, &document);
+}
+
+/// Print every value of the configuration file as `key.path = value` lines
+pub fn list_configuration() {
+    let document = read_config_document(Path::new(&get_config_path()));
+    let mut lines = Vec::new();
+    list_item(document.as_item(), "", &mut lines);
+    for line in lines {
+        println!("{}", line);
+    }
+}
 
-            table.insert(keys[0].to_string(), Value::Table(updated_values));
+/// Rewrite the options of the configuration file replaced by past releases
+///
+/// The changes are only printed, unless `write` is set.
+pub fn migrate_configuration(write: bool) {
+    let config_path = PathBuf::from(get_config_path());
+    let mut document = read_config_document(&config_path);
+    let original = document.to_string();
+
+    let deprecations = migrations::migrate(&mut document);
+    if deprecations.is_empty() {
+        println!(
+            "No replaced options found in {}",
+            config_path.to_string_lossy()
+        );
+        return;
+    }
+
+    for deprecation in &deprecations {
+        println!(
+            "`{}` was replaced by {} in {}",
+            deprecation.key(),
+            deprecation.migration.replacement,
+            deprecation.migration.version
+        );
+    }
+    println!();
+    print_diff(&original, &document.to_string());
+    println!();
+
+    if write {
+        save_config_document(&config_path, &document);
+        println!("Migrated {}", config_path.to_string_lossy());
+    } else {
+        println!(
+            "Run `starship config migrate --write` to update {}",
+            config_path.to_string_lossy()
+        );
+    }
+}
+
+/// Print the lines changed between two versions of a file, along with the lines around them
+fn print_diff(old: &str, new: &str) {
+    const CONTEXT_LINES: usize = 2;
+
+    let lines = diff::lines(old, new);
+    let is_changed: Vec<bool> = lines
+        .iter()
+        .map(|line| !matches!(line, diff::Result::Both(..)))
+        .collect();
+
+    let mut skipped = false;
+    for (index, line) in lines.iter().enumerate() {
+        let context_start = index.saturating_sub(CONTEXT_LINES);
+        let context_end = (index + CONTEXT_LINES + 1).min(lines.len());
+        if !is_changed[context_start..context_end].contains(&true) {
+            skipped = true;
+            continue;
+        }
+        if skipped {
+            println!("...");
+            skipped = false;
         }
 
-        let config_str =
-            toml::to_string_pretty(&table).expect("Failed to serialize the config to string");
-        File::create(&config_path)
-            .and_then(|mut file| file.write_all(config_str.as_ref()))
-            .expect("Error writing starship config");
+        match line {
+            diff::Result::Left(line) => println!("-{}", line),
+            diff::Result::Right(line) => println!("+{}", line),
+            diff::Result::Both(line, _) => println!(" {}", line),
+        }
+    }
+    if skipped {
+        println!("...");
     }
 }
 
@@ -90,6 +565,289 @@ pub fn edit_configuration() {
     };
 }
 
+pub fn check_configuration() {
+    let current_dir = env::current_dir().ok();
+    // The errors logged while loading the files are reported below, with their line
+    let max_level = log::max_level();
+    log::set_max_level(log::LevelFilter::Off);
+    let layers = StarshipConfig::layers(current_dir.as_deref());
+    log::set_max_level(max_level);
+    let resolved_config = StarshipConfig::from_layers(&layers)
+        .config
+        .unwrap_or_else(|| Value::Table(Map::new()));
+
+    // Every configuration file is optional, a missing one has nothing to check
+    let config_paths: Vec<(PathBuf, bool)> = StarshipConfig::file_sources(current_dir.as_deref())
+        .into_iter()
+        .filter_map(|source| match source {
+            ConfigSource::System(path) | ConfigSource::User(path) => Some((path, true)),
+            ConfigSource::Project(path) => {
+                let is_trusted = config::is_trusted_project(&path, &resolved_config);
+                Some((path, is_trusted))
+            }
+            _ => None,
+        })
+        .filter(|(path, _)| path.is_file())
+        .collect();
+
+    let mut checked = Vec::new();
+    let mut is_valid = true;
+    for (config_path, is_trusted) in &config_paths {
+        let canonical_path = fs::canonicalize(config_path).unwrap_or_else(|_| config_path.clone());
+        if !checked.contains(&canonical_path) {
+            is_valid &= check_file(config_path, *is_trusted, &resolved_config, &mut checked);
+        }
+    }
+
+    if !is_valid {
+        process::exit(1);
+    }
+    if config_paths.is_empty() {
+        println!("No configuration file found, using the default configuration");
+    }
+    for (config_path, _) in &config_paths {
+        println!("No errors found in {}", config_path.to_string_lossy());
+    }
+}
+
+/// Check a configuration file and the files it includes, printing the errors found
+///
+/// The includes of a project file which isn't trusted aren't loaded, so they aren't checked.
+/// `resolved_config` is the configuration merged from every layer, and `checked` holds the
+/// files already checked, so that each file is only reported once.
+fn check_file(
+    config_path: &Path,
+    is_trusted: bool,
+    resolved_config: &Value,
+    checked: &mut Vec<PathBuf>,
+) -> bool {
+    let config_path_str = config_path.to_string_lossy();
+    checked.push(fs::canonicalize(config_path).unwrap_or_else(|_| config_path.into()));
+
+    let toml_content = match fs::read_to_string(config_path) {
+        Ok(content) => content,
+        Err(error) => {
+            eprintln!("Unable to read {}: {}", config_path_str, error);
+            return false;
+        }
+    };
+
+    let config: Value = match toml::from_str(&toml_content) {
+        Ok(config) => config,
+        Err(error) => {
+            eprintln!("{}: {}", config_path_str, error);
+            return false;
+        }
+    };
+
+    let mut errors = configs::validate(&config, resolved_config);
+    let mut included_files = Vec::new();
+    if !is_trusted {
+        for key in config::TRUSTED_PROJECT_KEYS {
+            if config.get(key).is_some() {
+                errors.push(ConfigError::untrusted_project_key(key));
+            }
+        }
+    }
+    let includes = if is_trusted {
+        config::include_paths(&config, config_path)
+    } else {
+        Vec::new()
+    };
+    for include in includes {
+        if !include.is_file() {
+            errors.push(ConfigError::missing_include(&include));
+        } else if !checked.contains(&fs::canonicalize(&include).unwrap_or_else(|_| include.clone()))
+        {
+            included_files.push(include);
+        }
+    }
+
+    let mut located_errors: Vec<_> = errors
+        .iter()
+        .map(|error| (config::find_line(&toml_content, &error.path), error))
+        .collect();
+    located_errors.sort_by_key(|(line, _)| *line);
+
+    for (line, error) in &located_errors {
+        match line {
+            Some(line) => eprintln!("{}:{}: {}", config_path_str, line, error),
+            None => eprintln!("{}: {}", config_path_str, error),
+        }
+    }
+
+    let mut is_valid = located_errors.is_empty();
+    for include in included_files {
+        is_valid &= check_file(&include, true, resolved_config, checked);
+    }
+    is_valid
+}
+
+pub fn print_defaults() {
+    let current_dir = env::current_dir().ok();
+    let layers = StarshipConfig::layers(current_dir.as_deref());
+    let starship_config = StarshipConfig::from_layers(&layers);
+
+    println!("# Each option is marked with where its value comes from:");
+    println!("#   default: the default value of the option");
+    for layer in &layers {
+        match &layer.source {
+            ConfigSource::Symbols(symbols) => {
+                println!("#   {}: the {} symbols", layer.source, symbols.name())
+            }
+            ConfigSource::Preset(name) => println!("#   {}: the {} preset", layer.source, name),
+            ConfigSource::System(path) | ConfigSource::User(path) | ConfigSource::Project(path) => {
+                println!("#   {}: {}", layer.source, path.to_string_lossy())
+            }
+            ConfigSource::Theme(theme) => {
+                println!(
+                    "#   {}: the [theme.{}] overrides",
+                    layer.source,
+                    theme.name()
+                )
+            }
+            ConfigSource::Environment => {
+                println!(
+                    "#   {}: the STARSHIP__module__key environment variables",
+                    layer.source
+                )
+            }
+        }
+    }
+
+    for (path, table) in configs::resolve(&starship_config) {
+        let table = match table.as_table() {
+            Some(table) => table,
+            None => continue,
+        };
+
+        if !path.is_empty() {
+            let header = path
+                .iter()
+                .fold(String::new(), |prefix, key| join_key_path(&prefix, key));
+            println!("\n[{}]", header);
+        }
+
+        for (key, value) in table.iter() {
+            let mut key_path = path.clone();
+            key_path.push(key.clone());
+            let source = value_source(&layers, &key_path, value)
+                .map(ToString::to_string)
+                .unwrap_or_else(|| "default".to_string());
+            println!(
+                "{} = {} # {}",
+                join_key_path("", key),
+                format_value(value),
+                source
+            );
+        }
+    }
+}
+
+/// Find the layer which set the value at `path`, or `None` if it has its default value
+fn value_source<'a>(
+    layers: &'a [ConfigLayer],
+    path: &[String],
+    value: &Value,
+) -> Option<&'a ConfigSource> {
+    let mut layer_values = layers.iter().rev().filter_map(|layer| {
+        let layer_value = path
+            .iter()
+            .try_fold(&layer.config, |config, key| config.get(key))?;
+        Some((&layer.source, layer_value))
+    });
+
+    // Tables can be merged from several layers, they come from the last layer setting them
+    if value.is_table() {
+        return layer_values.next().map(|(source, _)| source);
+    }
+    layer_values
+        .find(|(_, layer_value)| *layer_value == value)
+        .map(|(source, _)| source)
+}
+
+/// Format a toml value on a single line, using inline tables
+fn format_value(value: &Value) -> String {
+    match value {
+        Value::Table(table) if table.is_empty() => "{}".to_string(),
+        Value::Table(table) => {
+            let entries: Vec<String> = table
+                .iter()
+                .map(|(key, value)| format!("{} = {}", join_key_path("", key), format_value(value)))
+                .collect();
+            format!("{{ {} }}", entries.join(", "))
+        }
+        Value::Array(array) => {
+            let items: Vec<String> = array.iter().map(format_value).collect();
+            format!("[{}]", items.join(", "))
+        }
+        value => value.to_string(),
+    }
+}
+
+pub fn print_presets() {
+    println!("Bundled presets");
+    println!("---------------");
+    let name_width = presets::PRESETS
+        .iter()
+        .map(|preset| preset.name.len())
+        .max()
+        .unwrap_or_default();
+    for preset in presets::PRESETS {
+        println!(
+            "{:width$}  {}",
+            preset.name,
+            preset.description,
+            width = name_width
+        );
+    }
+}
+
+pub fn print_preset(name: &str) {
+    match presets::get_preset(name) {
+        Some(preset) => print!("{}", preset.config),
+        None => {
+            eprintln!("Unknown preset `{}`", name);
+            process::exit(1);
+        }
+    }
+}
+
+/// Replace the configuration file with a preset, after copying the existing file to the
+/// first free `<config>.bak`, `<config>.bak.1`, ... path
+pub fn write_preset(name: &str) {
+    let preset = match presets::get_preset(name) {
+        Some(preset) => preset,
+        None => {
+            eprintln!("Unknown preset `{}`", name);
+            process::exit(1);
+        }
+    };
+    let document = preset
+        .config
+        .parse::<Document>()
+        .expect("presets are valid configuration files");
+    let config_path = PathBuf::from(get_config_path());
+    if let Some(backup_path) = save_config_document(&config_path, &document) {
+        println!(
+            "Backed up {} to {}",
+            config_path.to_string_lossy(),
+            backup_path.to_string_lossy()
+        );
+    }
+    println!(
+        "Wrote the {} preset to {}",
+        preset.name,
+        config_path.to_string_lossy()
+    );
+}
+
+pub fn print_schema() {
+    let schema = serde_json::to_string_pretty(&configs::schema())
+        .expect("Failed to serialize the config schema");
+    println!("{}", schema);
+}
+
 fn get_editor() -> OsString {
     get_editor_internal(env::var_os("VISUAL"), env::var_os("EDITOR"))
 }
@@ -172,4 +930,167 @@ mod tests {
         let actual = get_editor_internal(None, None);
         assert_eq!("vi", actual);
     }
+
+    #[test]
+    fn test_parse_key_path() {
+        use KeySegment::*;
+        assert_eq!(
+            parse_key_path("battery.display[1].style"),
+            Some(vec![
+                Key("battery".to_string()),
+                Key("display".to_string()),
+                Index(1),
+                Key("style".to_string()),
+            ])
+        );
+        assert_eq!(
+            parse_key_path("custom.\"my.module\".files[0]"),
+            Some(vec![
+                Key("custom".to_string()),
+                Key("my.module".to_string()),
+                Key("files".to_string()),
+                Index(0),
+            ])
+        );
+        assert_eq!(parse_key_path(""), None);
+        assert_eq!(parse_key_path("character."), None);
+        assert_eq!(parse_key_path("[0]"), None);
+        assert_eq!(parse_key_path("display[x]"), None);
+        assert_eq!(parse_key_path("custom.\"foo"), None);
+    }
+
+    #[test]
+    fn test_set_item_keeps_formatting() {
+        let mut document = r#"# My configuration
+add_newline = false  # keep it short
+
+[character]
+success_symbol = "[>](bold green)"
+
+[[battery.display]]
+threshold = 10
+"#
+        .parse::<Document>()
+        .unwrap();
+
+        for (key, value) in &[
+            ("add_newline", "true"),
+            ("character.error_symbol", "[x](bold red)"),
+            ("battery.display[0].style", "red"),
+            ("battery.display[1]", "{ threshold = 30 }"),
+            ("custom.foo.files", "[\"foo.txt\"]"),
+            ("custom.foo.files[1]", "bar.txt"),
+            ("custom.foo.extensions[0]", "rs"),
+            ("custom.bar[0].command", "echo bar"),
+        ] {
+            let path = parse_key_path(key).unwrap();
+            set_item(&mut document, &path, parse_edit_value(value)).unwrap();
+        }
+
+        let expected = r#"# My configuration
+add_newline = true  # keep it short
+
+[character]
+success_symbol = "[>](bold green)"
+error_symbol = "[x](bold red)"
+
+[[battery.display]]
+threshold = 10
+style = "red"
+
+[[battery.display]]
+threshold = 30
+
+[custom.foo]
+files = ["foo.txt", "bar.txt"]
+extensions = ["rs"]
+
+[[custom.bar]]
+command = "echo bar"
+"#;
+        assert_eq!(document.to_string(), expected);
+    }
+
+    #[test]
+    fn test_set_item_errors() {
+        let mut document = "add_newline = false\nfiles = [\"a\"]\n"
+            .parse::<Document>()
+            .unwrap();
+        let mut set = |key| set_item(&mut document, &parse_key_path(key).unwrap(), 1.into());
+
+        assert_eq!(
+            set("add_newline.foo"),
+            Err("`add_newline` is not a table".into())
+        );
+        assert_eq!(set("files[2]"), Err("`files[2]` is out of range".into()));
+        assert_eq!(
+            set("add_newline[0]"),
+            Err("`add_newline` is not an array".into())
+        );
+    }
+
+    #[test]
+    fn test_unset_item() {
+        let mut document = r#"[character]
+success_symbol = "[>](bold green)" # green
+error_symbol = "[x](bold red)"
+
+[custom.foo]
+files = ["foo.txt", "bar.txt"]
+"#
+        .parse::<Document>()
+        .unwrap();
+
+        assert!(unset_item(
+            &mut document,
+            &parse_key_path("character.success_symbol").unwrap()
+        ));
+        assert!(unset_item(
+            &mut document,
+            &parse_key_path("custom.foo.files[0]").unwrap()
+        ));
+        assert!(!unset_item(
+            &mut document,
+            &parse_key_path("custom.bar").unwrap()
+        ));
+        assert!(!unset_item(
+            &mut document,
+            &parse_key_path("custom.foo.files[1]").unwrap()
+        ));
+
+        let expected = r#"[character]
+error_symbol = "[x](bold red)"
+
+[custom.foo]
+files = ["bar.txt"]
+"#;
+        assert_eq!(document.to_string(), expected);
+    }
+
+    #[test]
+    fn test_list_item() {
+        let document = r#"add_newline = false
+
+[[battery.display]]
+threshold = 10 # low
+
+[custom."my.module"]
+command = "echo foo"
+when = { files = ["foo.txt"] }
+"#
+        .parse::<Document>()
+        .unwrap();
+
+        let mut lines = Vec::new();
+        list_item(document.as_item(), "", &mut lines);
+        assert_eq!(
+            lines,
+            vec![
+                "add_newline = false",
+                "battery.display[0].threshold = 10",
+                "custom.\"my.module\".command = \"echo foo\"",
+                "custom.\"my.module\".when.files = [\"foo.txt\"]",
+            ]
+        );
+    }
 }
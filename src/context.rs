@@ -1,4 +1,4 @@
-use crate::config::StarshipConfig;
+use crate::config::{ColorDepth, StarshipConfig};
 use crate::module::Module;
 
 use crate::modules;
@@ -33,6 +33,27 @@ pub struct Context<'a> {
 
     /// The shell the user is assumed to be running
     pub shell: Shell,
+
+    /// Construct the right prompt instead of the left prompt
+    pub right: bool,
+
+    /// Construct the transient prompt, which replaces the prompt of a command once it is run
+    pub transient: bool,
+
+    /// Construct the continuation prompt, shown on the lines of a multi-line command
+    pub continuation: bool,
+
+    /// The colors the terminal is able to display
+    pub color_depth: ColorDepth,
+
+    /// Hide the sensitive parts of the prompt, such as the hostname
+    pub redact: bool,
+
+    /// The width of the terminal, in columns
+    pub width: usize,
+
+    /// The modules rendered in their short form, to fit the prompt in the terminal
+    pub shortened: HashSet<String>,
 }
 
 impl<'a> Context<'a> {
@@ -58,8 +79,6 @@ impl<'a> Context<'a> {
     where
         T: Into<PathBuf>,
     {
-        let config = StarshipConfig::initialize();
-
         // Unwrap the clap arguments into a simple hashtable
         // we only care about single arguments at this point, there isn't a
         // use-case for a list of arguments yet.
@@ -73,8 +92,22 @@ impl<'a> Context<'a> {
         // TODO: Currently gets the physical directory. Get the logical directory.
         let current_dir = Context::expand_tilde(dir.into());
 
+        let config = StarshipConfig::initialize(&current_dir);
+
         let shell = Context::get_shell();
 
+        let right = arguments.is_present("right");
+
+        let transient = arguments.is_present("transient");
+
+        let continuation = arguments.is_present("continuation");
+
+        let color_depth = Context::get_color_depth(&config);
+
+        let redact = Context::get_redact(&config);
+
+        let width = Context::get_width(&properties);
+
         Context {
             config,
             properties,
@@ -82,6 +115,13 @@ impl<'a> Context<'a> {
             dir_contents: OnceCell::new(),
             repo: OnceCell::new(),
             shell,
+            right,
+            transient,
+            continuation,
+            color_depth,
+            redact,
+            width,
+            shortened: HashSet::new(),
         }
     }
 
@@ -164,17 +204,55 @@ impl<'a> Context<'a> {
         })
     }
 
-    fn get_shell() -> Shell {
+    pub fn get_shell() -> Shell {
         let shell = std::env::var("STARSHIP_SHELL").unwrap_or_default();
         match shell.as_str() {
             "bash" => Shell::Bash,
+            "elvish" => Shell::Elvish,
             "fish" => Shell::Fish,
             "ion" => Shell::Ion,
+            "nu" => Shell::Nu,
             "powershell" => Shell::PowerShell,
+            "tcsh" => Shell::Tcsh,
+            "xonsh" => Shell::Xonsh,
             "zsh" => Shell::Zsh,
             _ => Shell::Unknown,
         }
     }
+
+    /// The `color_depth` option takes precedence over a non-empty `$NO_COLOR`, which
+    /// takes precedence over the depth guessed from the terminal
+    fn get_color_depth(config: &StarshipConfig) -> ColorDepth {
+        if let Some(color_depth) = config.get_root_config().color_depth {
+            return color_depth;
+        }
+        if matches!(env::var_os("NO_COLOR"), Some(value) if !value.is_empty()) {
+            return ColorDepth::NoColor;
+        }
+        ColorDepth::detect(
+            env::var("COLORTERM").ok().as_deref(),
+            env::var("TERM").ok().as_deref(),
+        )
+    }
+
+    /// `--terminal-width` takes precedence over the size of the terminal, which falls back to
+    /// 80 columns when starship isn't attached to one
+    fn get_width(properties: &HashMap<&str, String>) -> usize {
+        properties
+            .get("terminal_width")
+            .and_then(|width| width.trim().parse().ok())
+            .or_else(|| term_size::dimensions().map(|(width, _)| width))
+            .unwrap_or(80)
+    }
+
+    /// `$STARSHIP_REDACT` takes precedence over the `redact` option, and disables redaction when
+    /// set to `0` or `false`
+    fn get_redact(config: &StarshipConfig) -> bool {
+        match env::var("STARSHIP_REDACT") {
+            Ok(value) if !value.is_empty() => !matches!(value.as_str(), "0" | "false"),
+            _ => config.get_root_config().redact,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -337,13 +415,35 @@ fn get_current_branch(repository: &Repository) -> Option<String> {
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Shell {
     Bash,
+    Elvish,
     Fish,
     Ion,
+    Nu,
     PowerShell,
+    Tcsh,
+    Xonsh,
     Zsh,
     Unknown,
 }
 
+impl Shell {
+    /// The name of the shell, as given in `$STARSHIP_SHELL`
+    pub fn name(self) -> &'static str {
+        match self {
+            Shell::Bash => "bash",
+            Shell::Elvish => "elvish",
+            Shell::Fish => "fish",
+            Shell::Ion => "ion",
+            Shell::Nu => "nu",
+            Shell::PowerShell => "powershell",
+            Shell::Tcsh => "tcsh",
+            Shell::Xonsh => "xonsh",
+            Shell::Zsh => "zsh",
+            Shell::Unknown => "unknown",
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -37,6 +37,9 @@ fn _parse_format(format: Pair<Rule>) -> Vec<FormatElement> {
             Rule::text => result.push(FormatElement::Text(_parse_text(pair).into())),
             Rule::variable => result.push(FormatElement::Variable(_parse_variable(pair).into())),
             Rule::textgroup => result.push(FormatElement::TextGroup(_parse_textgroup(pair))),
+            Rule::conditional => result.push(FormatElement::Conditional(_parse_format(
+                pair.into_inner().next().unwrap(),
+            ))),
             _ => unreachable!(),
         }
     }
@@ -68,6 +71,9 @@ pub fn parse(format: &str) -> Result<Vec<FormatElement>, Error<Rule>> {
             Rule::text => result.push(FormatElement::Text(_parse_text(pair).into())),
             Rule::variable => result.push(FormatElement::Variable(_parse_variable(pair).into())),
             Rule::textgroup => result.push(FormatElement::TextGroup(_parse_textgroup(pair))),
+            Rule::conditional => result.push(FormatElement::Conditional(_parse_format(
+                pair.into_inner().next().unwrap(),
+            ))),
             _ => unreachable!(),
         }
     }
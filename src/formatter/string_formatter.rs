@@ -3,47 +3,91 @@ use pest::error::Error;
 use rayon::prelude::*;
 use std::collections::BTreeMap;
 
-use crate::config::parse_style_string;
+use crate::config::parse_style_string_with_prev;
+use crate::context::Context;
 use crate::segment::Segment;
 
 use super::model::*;
 use super::parser::{parse, Rule};
 
 #[derive(Clone)]
-enum VariableValue {
+enum VariableValue<'a> {
     Plain(String),
     Styled(Vec<Segment>),
+    Meta(Vec<FormatElement<'a>>),
 }
 
-impl Default for VariableValue {
+impl<'a> Default for VariableValue<'a> {
     fn default() -> Self {
         VariableValue::Plain(String::new())
     }
 }
 
-type VariableMapType = BTreeMap<String, Option<VariableValue>>;
+type VariableMapType<'a> = BTreeMap<String, Option<VariableValue<'a>>>;
+type StyleVariableMapType = BTreeMap<String, Option<String>>;
 
+#[derive(Clone)]
 pub struct StringFormatter<'a> {
     format: Vec<FormatElement<'a>>,
-    variables: VariableMapType,
+    variables: VariableMapType<'a>,
+    style_variables: StyleVariableMapType,
 }
 
 impl<'a> StringFormatter<'a> {
     /// Creates an instance of StringFormatter from a format string
     pub fn new(format: &'a str) -> Result<Self, Error<Rule>> {
-        parse(format)
-            .map(|format| {
-                let variables = _get_variables(&format);
-                (format, variables)
-            })
-            .map(|(format, variables)| Self { format, variables })
+        parse(format).map(|format| {
+            let (variables, style_variables) = _get_variables(&format);
+            Self {
+                format,
+                variables,
+                style_variables,
+            }
+        })
+    }
+
+    /// Returns the names of the variables used in the format string, in order of appearance
+    pub fn get_variables(&self) -> Vec<String> {
+        fn _push_variables(names: &mut Vec<String>, format: &[FormatElement]) {
+            for el in format {
+                match el {
+                    FormatElement::Variable(name)
+                        if !names.iter().any(|existing| existing == name) =>
+                    {
+                        names.push(name.to_string())
+                    }
+                    FormatElement::TextGroup(textgroup) => {
+                        _push_variables(names, &textgroup.format)
+                    }
+                    FormatElement::Conditional(format) => _push_variables(names, format),
+                    _ => {}
+                }
+            }
+        }
+
+        let mut names = Vec::new();
+        _push_variables(&mut names, &self.format);
+        names
     }
 
     /// Maps variable name to its value
+    ///
+    /// Variables which already have a value are left untouched, which allows mappers to be
+    /// chained. Variables used in style strings which haven't been resolved by `map_style` are
+    /// mapped with the same closure, so modules can compute a style at render time.
     pub fn map(mut self, mapper: impl Fn(&str) -> Option<String> + Sync) -> Self {
-        self.variables.par_iter_mut().for_each(|(key, value)| {
-            *value = mapper(key).map(VariableValue::Plain);
-        });
+        self.variables
+            .par_iter_mut()
+            .filter(|(_, value)| value.is_none())
+            .for_each(|(key, value)| {
+                *value = mapper(key).map(VariableValue::Plain);
+            });
+        self.style_variables
+            .par_iter_mut()
+            .filter(|(_, value)| value.is_none())
+            .for_each(|(key, value)| {
+                *value = mapper(key);
+            });
         self
     }
 
@@ -52,58 +96,139 @@ impl<'a> StringFormatter<'a> {
         mut self,
         mapper: impl Fn(&str) -> Option<Vec<Segment>> + Sync,
     ) -> Self {
-        self.variables.par_iter_mut().for_each(|(key, value)| {
-            *value = mapper(key).map(VariableValue::Styled);
-        });
+        self.variables
+            .par_iter_mut()
+            .filter(|(_, value)| value.is_none())
+            .for_each(|(key, value)| {
+                *value = mapper(key).map(VariableValue::Styled);
+            });
+        self
+    }
+
+    /// Maps a meta-variable to a format string
+    ///
+    /// The returned format string is parsed and substituted in place of the variable. Variables
+    /// found in the substituted format string can be mapped afterwards with the other mappers.
+    pub fn map_meta(mut self, mapper: impl Fn(&str) -> Option<&'a str>) -> Self {
+        let keys = self
+            .variables
+            .iter()
+            .filter(|(_, value)| value.is_none())
+            .map(|(key, _)| key.to_owned())
+            .collect::<Vec<String>>();
+
+        for key in keys {
+            let format = match mapper(&key).map(parse) {
+                Some(Ok(format)) => format,
+                Some(Err(error)) => {
+                    log::warn!("Error parsing format string of `${}`: {}", key, error);
+                    continue;
+                }
+                None => continue,
+            };
+
+            let (variables, style_variables) = _get_variables(&format);
+            for (name, value) in variables {
+                self.variables.entry(name).or_insert(value);
+            }
+            for (name, value) in style_variables {
+                self.style_variables.entry(name).or_insert(value);
+            }
+            self.variables
+                .insert(key, Some(VariableValue::Meta(format)));
+        }
+        self
+    }
+
+    /// Maps variables used in style strings to their value
+    pub fn map_style(mut self, mapper: impl Fn(&str) -> Option<&'a str> + Sync) -> Self {
+        self.style_variables
+            .par_iter_mut()
+            .filter(|(_, value)| value.is_none())
+            .for_each(|(key, value)| {
+                *value = mapper(key).map(|style| style.to_owned());
+            });
         self
     }
 
     /// Parse the format string and consume self.
-    pub fn parse(self, default_style: Option<Style>) -> Vec<Segment> {
+    ///
+    /// The config of `context` is used to resolve the color names of the palette.
+    pub fn parse(self, default_style: Option<Style>, context: Option<&Context>) -> Vec<Segment> {
         fn _parse_textgroup<'a>(
             textgroup: TextGroup<'a>,
-            variables: &'a VariableMapType,
+            variables: &'a VariableMapType<'a>,
+            style_variables: &'a StyleVariableMapType,
+            context: Option<&Context>,
+            prev_style: Option<Style>,
         ) -> Vec<Segment> {
-            let style = _parse_style(textgroup.style);
-            _parse_format(textgroup.format, style, &variables)
+            let style = _parse_style(textgroup.style, style_variables, context, prev_style);
+            _parse_format(
+                textgroup.format,
+                style,
+                variables,
+                style_variables,
+                context,
+                prev_style,
+            )
         }
 
-        fn _parse_style(style: Vec<StyleElement>) -> Option<Style> {
+        fn _parse_style(
+            style: Vec<StyleElement>,
+            variables: &StyleVariableMapType,
+            context: Option<&Context>,
+            prev_style: Option<Style>,
+        ) -> Option<Style> {
             let style_string = style
                 .iter()
-                .flat_map(|style| match style {
-                    StyleElement::Text(text) => text.as_ref().chars(),
-                    StyleElement::Variable(variable) => {
-                        log::warn!(
-                            "Variable `{}` monitored in style string, which is not allowed",
-                            &variable
-                        );
-                        "".chars()
+                .map(|style| match style {
+                    StyleElement::Text(text) => text.to_string(),
+                    StyleElement::Variable(name) => {
+                        let style = variables.get(name.as_ref()).cloned().flatten();
+                        if style.is_none() {
+                            log::warn!("Style variable `{}` is not defined", &name);
+                        }
+                        style.unwrap_or_default()
                     }
                 })
                 .collect::<String>();
-            parse_style_string(&style_string)
+            parse_style_string_with_prev(&style_string, prev_style, context)
         }
 
         fn _parse_format<'a>(
             mut format: Vec<FormatElement<'a>>,
             style: Option<Style>,
-            variables: &'a VariableMapType,
+            variables: &'a VariableMapType<'a>,
+            style_variables: &'a StyleVariableMapType,
+            context: Option<&Context>,
+            prev_style: Option<Style>,
         ) -> Vec<Segment> {
             let mut result: Vec<Segment> = Vec::new();
 
             format.reverse();
             while let Some(el) = format.pop() {
+                // The style of the last segment, used by the `prev_fg` and `prev_bg` tokens
+                let prev_style = result.last().map_or(prev_style, |segment| segment.style);
                 let mut segments = match el {
                     FormatElement::Text(text) => {
                         vec![_new_segment("_text".into(), text.into_owned(), style)]
                     }
                     FormatElement::TextGroup(textgroup) => {
-                        let textgroup = TextGroup {
-                            format: textgroup.format,
-                            style: textgroup.style,
-                        };
-                        _parse_textgroup(textgroup, &variables)
+                        _parse_textgroup(textgroup, variables, style_variables, context, prev_style)
+                    }
+                    FormatElement::Conditional(format) => {
+                        if _should_show_elements(&format, variables) {
+                            _parse_format(
+                                format,
+                                style,
+                                variables,
+                                style_variables,
+                                context,
+                                prev_style,
+                            )
+                        } else {
+                            Vec::new()
+                        }
                     }
                     FormatElement::Variable(name) => variables
                         .get(name.as_ref())
@@ -124,6 +249,14 @@ impl<'a> StringFormatter<'a> {
                                 VariableValue::Plain(text) => {
                                     vec![_new_segment(name.to_string(), text, style)]
                                 }
+                                VariableValue::Meta(format) => _parse_format(
+                                    format,
+                                    style,
+                                    variables,
+                                    style_variables,
+                                    context,
+                                    prev_style,
+                                ),
                             }
                         })
                         .unwrap_or_default(),
@@ -134,49 +267,71 @@ impl<'a> StringFormatter<'a> {
             result
         }
 
-        _parse_format(self.format, default_style, &self.variables)
+        _parse_format(
+            self.format,
+            default_style,
+            &self.variables,
+            &self.style_variables,
+            context,
+            None,
+        )
     }
 }
 
 /// Extract variable names from an array of `FormatElement` into a `BTreeMap`
-fn _get_variables<'a>(format: &[FormatElement<'a>]) -> VariableMapType {
+///
+/// Variables used in the format and variables used in style strings are returned separately.
+fn _get_variables<'a>(format: &[FormatElement]) -> (VariableMapType<'a>, StyleVariableMapType) {
     let mut variables: VariableMapType = Default::default();
+    let mut style_variables: StyleVariableMapType = Default::default();
 
-    fn _push_variables_from_textgroup<'a>(
+    fn _push_variables_from_format(
         variables: &mut VariableMapType,
-        textgroup: &'a TextGroup<'a>,
+        style_variables: &mut StyleVariableMapType,
+        format: &[FormatElement],
     ) {
-        for el in &textgroup.format {
+        for el in format {
             match el {
-                FormatElement::Variable(name) => _push_variable(variables, name.as_ref()),
+                FormatElement::Variable(name) => {
+                    variables.insert(name.to_string(), None);
+                }
                 FormatElement::TextGroup(textgroup) => {
-                    _push_variables_from_textgroup(variables, &textgroup)
+                    _push_variables_from_format(variables, style_variables, &textgroup.format);
+                    for el in &textgroup.style {
+                        if let StyleElement::Variable(name) = el {
+                            style_variables.insert(name.to_string(), None);
+                        }
+                    }
+                }
+                FormatElement::Conditional(format) => {
+                    _push_variables_from_format(variables, style_variables, format);
                 }
                 _ => {}
             }
         }
-        for el in &textgroup.style {
-            if let StyleElement::Variable(name) = el {
-                _push_variable(variables, name.as_ref())
-            }
-        }
     }
 
-    fn _push_variable<'a>(variables: &mut VariableMapType, name: &'a str) {
-        variables.insert(name.to_owned(), None);
-    }
+    _push_variables_from_format(&mut variables, &mut style_variables, format);
 
-    for el in format {
-        match el {
-            FormatElement::Variable(name) => _push_variable(&mut variables, name.as_ref()),
-            FormatElement::TextGroup(textgroup) => {
-                _push_variables_from_textgroup(&mut variables, &textgroup)
-            }
-            _ => {}
-        }
-    }
+    (variables, style_variables)
+}
 
-    variables
+/// Whether a conditional format should be shown, i.e. whether any of the variables used
+/// inside it (including those of nested meta-variables) has a non-empty value
+fn _should_show_elements(format: &[FormatElement], variables: &VariableMapType) -> bool {
+    format.iter().any(|el| match el {
+        FormatElement::Text(_) => false,
+        FormatElement::TextGroup(textgroup) => _should_show_elements(&textgroup.format, variables),
+        FormatElement::Conditional(format) => _should_show_elements(format, variables),
+        FormatElement::Variable(name) => match variables.get(name.as_ref()) {
+            Some(Some(VariableValue::Plain(text))) => !text.is_empty(),
+            Some(Some(VariableValue::Styled(segments))) => {
+                segments.iter().any(|segment| !segment.is_empty())
+            }
+            Some(Some(VariableValue::Meta(format))) => _should_show_elements(format, variables),
+            Some(None) | None => false,
+        },
+    })
 }
 
 /// Helper function to create a new segment
@@ -212,7 +367,7 @@ mod tests {
         let style = Some(Color::Red.bold());
 
         let formatter = StringFormatter::new(FORMAT_STR).unwrap().map(empty_mapper);
-        let result = formatter.parse(style);
+        let result = formatter.parse(style, None);
         let mut result_iter = result.iter();
         match_next!(result_iter, "text", style);
     }
@@ -221,7 +376,7 @@ mod tests {
     fn test_textgroup_text_only() {
         const FORMAT_STR: &str = "[text](red bold)";
         let formatter = StringFormatter::new(FORMAT_STR).unwrap().map(empty_mapper);
-        let result = formatter.parse(None);
+        let result = formatter.parse(None, None);
         let mut result_iter = result.iter();
         match_next!(result_iter, "text", Some(Color::Red.bold()));
     }
@@ -236,17 +391,49 @@ mod tests {
                 "var1" => Some("text1".to_owned()),
                 _ => None,
             });
-        let result = formatter.parse(None);
+        let result = formatter.parse(None, None);
         let mut result_iter = result.iter();
         match_next!(result_iter, "text1", None);
     }
 
+    #[test]
+    fn test_get_variables() {
+        const FORMAT_STR: &str = "$b [$a$c]($style) $b";
+
+        let formatter = StringFormatter::new(FORMAT_STR).unwrap();
+        assert_eq!(formatter.get_variables(), vec!["b", "a", "c"]);
+    }
+
+    #[test]
+    fn test_custom_module_variable() {
+        const FORMAT_STR: &str = "$custom.foo. $custom";
+
+        let formatter = StringFormatter::new(FORMAT_STR).unwrap();
+        assert_eq!(formatter.get_variables(), vec!["custom.foo", "custom"]);
+    }
+
+    #[test]
+    fn test_braced_variable() {
+        const FORMAT_STR: &str = "${var1}_text";
+
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map(|variable| match variable {
+                "var1" => Some("text1".to_owned()),
+                _ => None,
+            });
+        let result = formatter.parse(None, None);
+        let mut result_iter = result.iter();
+        match_next!(result_iter, "text1", None);
+        match_next!(result_iter, "_text", None);
+    }
+
     #[test]
     fn test_escaped_chars() {
         const FORMAT_STR: &str = r#"\\\[\$text\]\(red bold\)"#;
 
         let formatter = StringFormatter::new(FORMAT_STR).unwrap().map(empty_mapper);
-        let result = formatter.parse(None);
+        let result = formatter.parse(None, None);
         let mut result_iter = result.iter();
         match_next!(result_iter, r#"\[$text](red bold)"#, None);
     }
@@ -259,7 +446,7 @@ mod tests {
         let inner_style = Some(Color::Blue.normal());
 
         let formatter = StringFormatter::new(FORMAT_STR).unwrap().map(empty_mapper);
-        let result = formatter.parse(outer_style);
+        let result = formatter.parse(outer_style, None);
         let mut result_iter = result.iter();
         match_next!(result_iter, "outer ", outer_style);
         match_next!(result_iter, "middle ", middle_style);
@@ -277,7 +464,7 @@ mod tests {
                 "var" => Some("text".to_owned()),
                 _ => None,
             });
-        let result = formatter.parse(None);
+        let result = formatter.parse(None, None);
         let mut result_iter = result.iter();
         match_next!(result_iter, "text", var_style);
     }
@@ -303,13 +490,214 @@ mod tests {
                 ]),
                 _ => None,
             });
-        let result = formatter.parse(None);
+        let result = formatter.parse(None, None);
         let mut result_iter = result.iter();
         match_next!(result_iter, "styless", var_style);
         match_next!(result_iter, "styled", styled_style);
         match_next!(result_iter, "styled_no_modifier", styled_no_modifier_style);
     }
 
+    #[test]
+    fn test_styled_variable_in_style_string() {
+        const FORMAT_STR: &str = "[$var]($style)";
+        let var_style = Some(Color::Red.bold());
+
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map_style(|variable| match variable {
+                "style" => Some("red bold"),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "var" => Some("text".to_owned()),
+                _ => None,
+            });
+        let result = formatter.parse(None, None);
+        let mut result_iter = result.iter();
+        match_next!(result_iter, "text", var_style);
+    }
+
+    #[test]
+    fn test_style_variable_from_map() {
+        const FORMAT_STR: &str = "[$branch]($branch_style) [$other]($style)";
+        let branch_style = Some(Color::Red.bold());
+        let other_style = Some(Color::Green.normal());
+
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map_style(|variable| match variable {
+                "style" => Some("green"),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "branch" => Some("master".to_owned()),
+                "branch_style" => Some("bold red".to_owned()),
+                "other" => Some("text".to_owned()),
+                "style" => Some("blue".to_owned()),
+                _ => None,
+            });
+        let result = formatter.parse(None, None);
+        let mut result_iter = result.iter();
+        match_next!(result_iter, "master", branch_style);
+        match_next!(result_iter, " ", None);
+        match_next!(result_iter, "text", other_style);
+    }
+
+    #[test]
+    fn test_meta_variable() {
+        const FORMAT_STR: &str = "$meta ";
+        const META_STR: &str = "[$var](red bold)";
+        let var_style = Some(Color::Red.bold());
+
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map_meta(|variable| match variable {
+                "meta" => Some(META_STR),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "var" => Some("text".to_owned()),
+                _ => None,
+            });
+        let result = formatter.parse(None, None);
+        let mut result_iter = result.iter();
+        match_next!(result_iter, "text", var_style);
+        match_next!(result_iter, " ", None);
+    }
+
+    #[test]
+    fn test_chained_mappers() {
+        const FORMAT_STR: &str = "$var1$var2";
+
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map(|variable| match variable {
+                "var1" => Some("text1".to_owned()),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "var1" => Some("overwritten".to_owned()),
+                "var2" => Some("text2".to_owned()),
+                _ => None,
+            });
+        let result = formatter.parse(None, None);
+        let mut result_iter = result.iter();
+        match_next!(result_iter, "text1", None);
+        match_next!(result_iter, "text2", None);
+    }
+
+    #[test]
+    fn test_conditional() {
+        const FORMAT_STR: &str = "($some) should render but ($none) shouldn't";
+
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map(|variable| match variable {
+                "some" => Some("$some".to_owned()),
+                _ => None,
+            });
+        let result = formatter.parse(None, None);
+        let mut result_iter = result.iter();
+        match_next!(result_iter, "$some", None);
+        match_next!(result_iter, " should render but ", None);
+        match_next!(result_iter, " shouldn't", None);
+        assert!(result_iter.next().is_none());
+    }
+
+    #[test]
+    fn test_conditional_with_empty_value() {
+        const FORMAT_STR: &str = "a( \\($empty\\))b";
+
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map(|variable| match variable {
+                "empty" => Some(String::new()),
+                _ => None,
+            });
+        let result = formatter.parse(None, None);
+        let mut result_iter = result.iter();
+        match_next!(result_iter, "a", None);
+        match_next!(result_iter, "b", None);
+        assert!(result_iter.next().is_none());
+    }
+
+    #[test]
+    fn test_nested_conditional() {
+        const FORMAT_STR: &str = "($some ($none)) and ($none ($some))";
+
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map(|variable| match variable {
+                "some" => Some("$some".to_owned()),
+                _ => None,
+            });
+        let result = formatter.parse(None, None);
+        let mut result_iter = result.iter();
+        match_next!(result_iter, "$some", None);
+        match_next!(result_iter, " ", None);
+        match_next!(result_iter, " and ", None);
+        match_next!(result_iter, "", None);
+        match_next!(result_iter, " ", None);
+        match_next!(result_iter, "$some", None);
+        assert!(result_iter.next().is_none());
+    }
+
+    #[test]
+    fn test_conditional_in_textgroup() {
+        const FORMAT_STR: &str = "[$some( \\($none\\))](red)";
+        let style = Some(Color::Red.normal());
+
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map(|variable| match variable {
+                "some" => Some("text".to_owned()),
+                _ => None,
+            });
+        let result = formatter.parse(None, None);
+        let mut result_iter = result.iter();
+        match_next!(result_iter, "text", style);
+        assert!(result_iter.next().is_none());
+    }
+
+    #[test]
+    fn test_conditional_meta_variable() {
+        const FORMAT_STR: &str = "($meta)";
+
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map_meta(|variable| match variable {
+                "meta" => Some("[$var](red)"),
+                _ => None,
+            })
+            .map(empty_mapper);
+        let result = formatter.parse(None, None);
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn test_prev_style_tokens() {
+        const FORMAT_STR: &str = "[a](fg:red bg:blue)[b](fg:prev_bg bg:green)[c](prev_fg)";
+
+        let formatter = StringFormatter::new(FORMAT_STR).unwrap();
+        let result = formatter.parse(None, None);
+        let mut result_iter = result.iter();
+        match_next!(result_iter, "a", Some(Color::Red.on(Color::Blue)));
+        match_next!(result_iter, "b", Some(Color::Blue.on(Color::Green)));
+        match_next!(result_iter, "c", Some(Color::Blue.normal()));
+        assert!(result_iter.next().is_none());
+    }
+
+    #[test]
+    fn test_prev_style_tokens_without_previous_segment() {
+        const FORMAT_STR: &str = "[a](bold fg:prev_bg)";
+
+        let formatter = StringFormatter::new(FORMAT_STR).unwrap();
+        let result = formatter.parse(None, None);
+        let mut result_iter = result.iter();
+        match_next!(result_iter, "a", Some(Style::new().bold()));
+        assert!(result_iter.next().is_none());
+    }
+
     #[test]
     fn test_parse_error() {
         // brackets without escape
@@ -322,5 +710,10 @@ mod tests {
             const FORMAT_STR: &str = "$ ";
             assert!(StringFormatter::new(FORMAT_STR).is_err());
         }
+        // Unclosed conditional
+        {
+            const FORMAT_STR: &str = "($var";
+            assert!(StringFormatter::new(FORMAT_STR).is_err());
+        }
     }
 }
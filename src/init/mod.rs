@@ -113,6 +113,28 @@ fi"#,
             let script = format!("eval $({} init ion --print-full-init)", starship);
             Some(script)
         }
+        Some("elvish") => {
+            let script = format!(
+                "eval (\"{}\" init elvish --print-full-init | slurp)",
+                starship
+            );
+            Some(script)
+        }
+        Some("nu") => {
+            // Nushell can't evaluate a script generated at runtime: the full init script is
+            // saved to a file, which is loaded with `use`
+            return init_main("nu");
+        }
+        Some("tcsh") => {
+            // Command substitution joins the lines of the init script, so it is written
+            // without comments, and with each command ending in a semicolon
+            let script = format!("eval `(\"{}\" init tcsh --print-full-init)`", starship);
+            Some(script)
+        }
+        Some("xonsh") => {
+            let script = format!("execx($(\"{}\" init xonsh --print-full-init))", starship);
+            Some(script)
+        }
         None => {
             println!(
                 "Invalid shell name provided: {}\\n\
@@ -126,7 +148,8 @@ fi"#,
         Some(shell_basename) => {
             println!(
                 "printf \"\\n{0} is not yet supported by starship.\\n\
-                 For the time being, we support bash, zsh, fish, and ion.\\n\
+                 For the time being, we support bash, elvish, fish, ion, nu, powershell, \
+                 tcsh, xonsh and zsh.\\n\
                  Please open an issue in the starship repo if you would like to \
                  see support for {0}:\\nhttps://github.com/starship/starship/issues/new\"\\n\\n",
                 shell_basename
@@ -151,6 +174,10 @@ pub fn init_main(shell_name: &str) -> io::Result<()> {
         "fish" => Some(FISH_INIT),
         "powershell" => Some(PWSH_INIT),
         "ion" => Some(ION_INIT),
+        "elvish" => Some(ELVISH_INIT),
+        "nu" => Some(NU_INIT),
+        "tcsh" => Some(TCSH_INIT),
+        "xonsh" => Some(XONSH_INIT),
         _ => {
             println!(
                 "printf \"Shell name detection failed on phase two init.\\n\
@@ -193,3 +220,14 @@ const FISH_INIT: &str = include_str!("starship.fish");
 const PWSH_INIT: &str = include_str!("starship.ps1");
 
 const ION_INIT: &str = include_str!("starship.ion");
+
+const ELVISH_INIT: &str = include_str!("starship.elv");
+
+const NU_INIT: &str = include_str!("starship.nu");
+
+// tcsh has neither a variable holding the number of jobs nor one holding the mode of its
+// line editor, so the tcsh prompt is rendered without `--jobs` and `--keymap`. The width of
+// the terminal is read with `printenv`, which unlike `$COLUMNS` doesn't fail when it's unset.
+const TCSH_INIT: &str = include_str!("starship.tcsh");
+
+const XONSH_INIT: &str = include_str!("starship.xsh");
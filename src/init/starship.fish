@@ -8,7 +8,47 @@ function fish_prompt
     set -l exit_code $status
     # Account for changes in variable name between v2.7 and v3.0
     set -l starship_duration "$CMD_DURATION$cmd_duration"
-    ::STARSHIP:: prompt --status=$exit_code --keymap=$keymap --cmd-duration=$starship_duration --jobs=(count (jobs -p))
+    if test "$STARSHIP_TRANSIENT" = 1
+        ::STARSHIP:: prompt --transient --status=$exit_code --terminal-width=$COLUMNS --keymap=$keymap --cmd-duration=$starship_duration --jobs=(count (jobs -p))
+    else
+        ::STARSHIP:: prompt --status=$exit_code --terminal-width=$COLUMNS --keymap=$keymap --cmd-duration=$starship_duration --jobs=(count (jobs -p))
+    end
+end
+
+function fish_right_prompt
+    switch "$fish_key_bindings"
+        case fish_hybrid_key_bindings fish_vi_key_bindings
+            set keymap "$fish_bind_mode"
+        case '*'
+            set keymap insert
+    end
+    set -l exit_code $status
+    # Account for changes in variable name between v2.7 and v3.0
+    set -l starship_duration "$CMD_DURATION$cmd_duration"
+    if test "$STARSHIP_TRANSIENT" = 1
+        # The right prompt is drawn last, so the transient prompt is complete
+        set -g STARSHIP_TRANSIENT 0
+        ::STARSHIP:: prompt --transient --right --status=$exit_code --terminal-width=$COLUMNS --keymap=$keymap --cmd-duration=$starship_duration --jobs=(count (jobs -p))
+    else
+        ::STARSHIP:: prompt --right --status=$exit_code --terminal-width=$COLUMNS --keymap=$keymap --cmd-duration=$starship_duration --jobs=(count (jobs -p))
+    end
+end
+
+# Once a command is accepted, redraw its prompt in the transient form, so that
+# the scrollback only keeps the command lines. Enabled by `enable_transience`.
+function starship_transient_execute
+    # An incomplete command line is continued instead of being run
+    set -l line (commandline)
+    if commandline --is-valid; or test -z "$line"
+        set -g STARSHIP_TRANSIENT 1
+        commandline -f repaint
+    end
+    commandline -f execute
+end
+
+function enable_transience
+    bind \r starship_transient_execute
+    bind -M insert \r starship_transient_execute
 end
 
 # disable virtualenv prompt, it breaks starship
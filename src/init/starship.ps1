@@ -10,17 +10,45 @@ function global:prompt {
     $env:PWD = $PWD
     $current_directory = (Convert-Path $PWD)
 
+    $width = $Host.UI.RawUI.WindowSize.Width
+    $arguments = @("--path=$current_directory", "--status=$lastexitcode", "--jobs=$jobs", "--terminal-width=$width")
     if ($lastCmd = Get-History -Count 1) {
         $duration = [math]::Round(($lastCmd.EndExecutionTime - $lastCmd.StartExecutionTime).TotalMilliseconds)
-        # & ensures the path is interpreted as something to execute
-        $out = @(&::STARSHIP:: prompt "--path=$current_directory" --status=$lastexitcode --jobs=$jobs --cmd-duration=$duration)
-    } else {
-        $out = @(&::STARSHIP:: prompt "--path=$current_directory" --status=$lastexitcode --jobs=$jobs)
+        $arguments += "--cmd-duration=$duration"
     }
+    if ($global:_starship_transient) {
+        $global:_starship_transient = $false
+        $arguments += "--transient"
+    }
+
+    # & ensures the path is interpreted as something to execute
+    $out = @(&::STARSHIP:: prompt $arguments)
+    $right = @(&::STARSHIP:: prompt --right $arguments) -join ""
 
     # Convert stdout (array of lines) to expected return type string
     # `n is an escaped newline
-    $out -join "`n"
+    $out = $out -join "`n"
+
+    # PowerShell has no native right prompt: starship measures it and moves the
+    # cursor to draw it at the end of the last line of the prompt
+    $out += $right
+
+    $out
+}
+
+# Once a command is accepted, redraw its prompt in the transient form, so that
+# the scrollback only keeps the command lines
+function global:Enable-TransientPrompt {
+    Set-PSReadLineKeyHandler -Key Enter -ScriptBlock {
+        $global:_starship_transient = $true
+        [Microsoft.PowerShell.PSConsoleReadLine]::InvokePrompt()
+        [Microsoft.PowerShell.PSConsoleReadLine]::AcceptLine()
+    }
 }
 
 $ENV:STARSHIP_SHELL = "powershell"
+
+# PSReadLine draws the prompt of the lines of a multi-line command
+if (Get-Module PSReadLine) {
+    Set-PSReadLineOption -ContinuationPrompt (&::STARSHIP:: prompt --continuation)
+}
@@ -8,6 +8,7 @@ pub mod context;
 pub mod formatter;
 pub mod module;
 pub mod modules;
+pub mod presets;
 pub mod print;
 pub mod segment;
 mod utils;
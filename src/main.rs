@@ -15,6 +15,7 @@ mod formatter;
 mod init;
 mod module;
 mod modules;
+mod presets;
 mod print;
 mod segment;
 mod utils;
@@ -42,7 +43,7 @@ fn main() {
     let shell_arg = Arg::with_name("shell")
         .value_name("SHELL")
         .help(
-            "The name of the currently running shell\nCurrently supported options: bash, zsh, fish, powershell, ion",
+            "The name of the currently running shell\nCurrently supported options: bash, elvish, fish, ion, nu, powershell, tcsh, xonsh, zsh",
         )
         .required(true);
 
@@ -68,10 +69,35 @@ fn main() {
         .help("The number of currently running jobs")
         .takes_value(true);
 
+    let terminal_width_arg = Arg::with_name("terminal_width")
+        .short("w")
+        .long("terminal-width")
+        .value_name("WIDTH")
+        .help("The width of the terminal, in columns")
+        .takes_value(true);
+
+    let right_arg = Arg::with_name("right")
+        .long("right")
+        .help("Print the right prompt (instead of the standard left prompt)");
+
+    let transient_arg = Arg::with_name("transient")
+        .long("transient")
+        .help("Print the transient prompt, which replaces the prompt of a command once it is run");
+
+    let continuation_arg = Arg::with_name("continuation")
+        .long("continuation")
+        .help("Print the continuation prompt, shown on the lines of a multi-line command");
+
     let init_scripts_arg = Arg::with_name("print_full_init")
         .long("print-full-init")
         .help("Print the main initialization script (as opposed to the init stub)");
 
+    let key_arg = Arg::with_name("key")
+        .help("The path of the key, e.g. `battery.display[0].style`")
+        .required(true);
+
+    let preset_names: Vec<&str> = presets::PRESETS.iter().map(|preset| preset.name).collect();
+
     let mut app =
         App::new("starship")
             .about("The cross-shell prompt for astronauts. ☄🌌️")
@@ -90,11 +116,15 @@ fn main() {
             .subcommand(
                 SubCommand::with_name("prompt")
                     .about("Prints the full starship prompt")
+                    .arg(&right_arg)
+                    .arg(&transient_arg)
+                    .arg(&continuation_arg)
                     .arg(&status_code_arg)
                     .arg(&path_arg)
                     .arg(&cmd_duration_arg)
                     .arg(&keymap_arg)
-                    .arg(&jobs_arg),
+                    .arg(&jobs_arg)
+                    .arg(&terminal_width_arg),
             )
             .subcommand(
                 SubCommand::with_name("module")
@@ -127,7 +157,83 @@ fn main() {
                             .required(false)
                             .requires("value"),
                     )
-                    .arg(Arg::with_name("value").help("Value to place into that key")),
+                    .arg(Arg::with_name("value").help("Value to place into that key"))
+                    .arg(
+                        Arg::with_name("check")
+                            .long("check")
+                            .help("Check the configuration file for errors")
+                            .conflicts_with("name"),
+                    )
+                    .arg(
+                        Arg::with_name("schema")
+                            .long("schema")
+                            .help("Print a JSON Schema of the configuration file")
+                            .conflicts_with_all(&["name", "check"]),
+                    )
+                    .arg(
+                        Arg::with_name("print_defaults")
+                            .long("print-defaults")
+                            .help("Print the configuration merged with the default values")
+                            .conflicts_with_all(&["name", "check", "schema"]),
+                    )
+                    .subcommand(
+                        SubCommand::with_name("get")
+                            .about("Print the value of a key of the configuration file")
+                            .arg(&key_arg),
+                    )
+                    .subcommand(
+                        SubCommand::with_name("set")
+                            .about("Set a key of the configuration file")
+                            .arg(&key_arg)
+                            .arg(
+                                Arg::with_name("value")
+                                    .help("The value, read as a TOML value when possible, e.g. `true`, `[\"a\"]` or `{ style = \"red\" }`")
+                                    .required(true),
+                            ),
+                    )
+                    .subcommand(
+                        SubCommand::with_name("unset")
+                            .about("Remove a key from the configuration file")
+                            .arg(&key_arg),
+                    )
+                    .subcommand(
+                        SubCommand::with_name("list")
+                            .about("Print every value of the configuration file"),
+                    )
+                    .subcommand(
+                        SubCommand::with_name("migrate")
+                            .about("Rewrite the options replaced by past releases")
+                            .arg(
+                                Arg::with_name("write")
+                                    .short("w")
+                                    .long("write")
+                                    .help("Write the changes to the configuration file, backing it up first"),
+                            ),
+                    ),
+            )
+            .subcommand(
+                SubCommand::with_name("preset")
+                    .about("Prints or writes a bundled configuration preset")
+                    .arg(
+                        Arg::with_name("name")
+                            .help("The name of the preset")
+                            .possible_values(&preset_names)
+                            .required(true)
+                            .required_unless("list"),
+                    )
+                    .arg(
+                        Arg::with_name("list")
+                            .short("l")
+                            .long("list")
+                            .help("List out all bundled presets"),
+                    )
+                    .arg(
+                        Arg::with_name("write")
+                            .short("w")
+                            .long("write")
+                            .help("Write the preset to the configuration file, backing it up first")
+                            .conflicts_with("list"),
+                    ),
             )
             .subcommand(SubCommand::with_name("bug-report").about(
                 "Create a pre-populated GitHub issue with information about your configuration",
@@ -179,12 +285,46 @@ fn main() {
             }
         }
         ("config", Some(sub_m)) => {
-            if let Some(name) = sub_m.value_of("name") {
+            if sub_m.is_present("check") {
+                configure::check_configuration()
+            } else if sub_m.is_present("schema") {
+                configure::print_schema()
+            } else if sub_m.is_present("print_defaults") {
+                configure::print_defaults()
+            } else if let Some(name) = sub_m.value_of("name") {
                 if let Some(value) = sub_m.value_of("value") {
-                    configure::update_configuration(name, value)
+                    configure::set_configuration(name, value)
                 }
             } else {
-                configure::edit_configuration()
+                match sub_m.subcommand() {
+                    ("get", Some(get_m)) => {
+                        configure::get_configuration(get_m.value_of("key").expect("key missing"))
+                    }
+                    ("set", Some(set_m)) => configure::set_configuration(
+                        set_m.value_of("key").expect("key missing"),
+                        set_m.value_of("value").expect("value missing"),
+                    ),
+                    ("unset", Some(unset_m)) => configure::unset_configuration(
+                        unset_m.value_of("key").expect("key missing"),
+                    ),
+                    ("list", Some(_)) => configure::list_configuration(),
+                    ("migrate", Some(migrate_m)) => {
+                        configure::migrate_configuration(migrate_m.is_present("write"))
+                    }
+                    _ => configure::edit_configuration(),
+                }
+            }
+        }
+        ("preset", Some(sub_m)) => {
+            if sub_m.is_present("list") {
+                configure::print_presets();
+            }
+            if let Some(name) = sub_m.value_of("name") {
+                if sub_m.is_present("write") {
+                    configure::write_preset(name)
+                } else {
+                    configure::print_preset(name)
+                }
             }
         }
         ("bug-report", Some(_)) => bug_report::create(),
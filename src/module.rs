@@ -1,8 +1,7 @@
-use crate::config::SegmentConfig;
+use crate::config::ColorDepth;
 use crate::context::Shell;
 use crate::segment::Segment;
 use crate::utils::wrap_colorseq_for_shell;
-use ansi_term::Style;
 use ansi_term::{ANSIString, ANSIStrings};
 use std::fmt;
 
@@ -23,6 +22,7 @@ pub const ALL_MODULES: &[&str] = &[
     "elm",
     "erlang",
     "env_var",
+    "fill",
     "git_branch",
     "git_commit",
     "git_state",
@@ -55,6 +55,37 @@ pub const ALL_MODULES: &[&str] = &[
     "zig",
 ];
 
+/// How a sensitive variable of a module is hidden when redaction is enabled
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Redaction {
+    /// Replace each occurrence of the home directory with `~`, which still shows the rest of
+    /// the value, e.g. the directories under the home directory
+    Home,
+    /// Replace the value with a short hash, so that different values can still be told apart
+    Hash,
+    /// Replace the value with asterisks
+    Mask,
+}
+
+impl Redaction {
+    pub fn apply(self, value: &str) -> String {
+        match self {
+            Redaction::Home => match dirs_next::home_dir() {
+                Some(home) => value.replace(home.to_string_lossy().as_ref(), "~"),
+                None => value.to_string(),
+            },
+            // 32-bit FNV-1a, which unlike the std hasher gives the same hash across releases
+            Redaction::Hash => {
+                let hash = value.bytes().fold(0x811c_9dc5_u32, |hash, byte| {
+                    (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+                });
+                format!("{:08x}", hash)
+            }
+            Redaction::Mask => "***".to_string(),
+        }
+    }
+}
+
 /// A module is a collection of segments showing data for a single integration
 /// (e.g. The git module shows the current git branch and status)
 pub struct Module<'a> {
@@ -67,17 +98,8 @@ pub struct Module<'a> {
     /// The module's description
     description: String,
 
-    /// The styling to be inherited by all segments contained within this module.
-    style: Style,
-
-    /// The prefix used to separate the current module from the previous one.
-    prefix: Affix,
-
     /// The collection of segments that compose this module.
-    segments: Vec<Segment>,
-
-    /// The suffix used to separate the current module from the next one.
-    suffix: Affix,
+    pub segments: Vec<Segment>,
 }
 
 impl<'a> Module<'a> {
@@ -87,28 +109,39 @@ impl<'a> Module<'a> {
             config,
             _name: name.to_string(),
             description: desc.to_string(),
-            style: Style::default(),
-            prefix: Affix::default_prefix(name),
             segments: Vec::new(),
-            suffix: Affix::default_suffix(name),
         }
     }
 
-    /// Get a reference to a newly created segment in the module
-    pub fn create_segment(&mut self, name: &str, segment_config: &SegmentConfig) -> &mut Segment {
-        let mut segment = Segment::new(name);
-        segment.set_style(segment_config.style.unwrap_or(self.style));
-        segment.set_value(segment_config.value);
-        self.segments.push(segment);
-
-        self.segments.last_mut().unwrap()
-    }
-
     /// Set segments in module
     pub fn set_segments(&mut self, segments: Vec<Segment>) {
         self.segments = segments;
     }
 
+    /// Map the style of each segment to the colors available at `color_depth`
+    pub fn set_color_depth(&mut self, color_depth: ColorDepth) {
+        for segment in &mut self.segments {
+            if let Some(style) = segment.style {
+                segment.set_style(color_depth.apply(style));
+            }
+        }
+    }
+
+    /// Hide the values of the segments of the variables listed in `redactions`
+    pub fn redact(&mut self, redactions: &[(&str, Redaction)]) {
+        for segment in &mut self.segments {
+            let redaction = redactions
+                .iter()
+                .find(|(name, _)| *name == segment._name)
+                .map(|(_, redaction)| *redaction);
+            if let Some(redaction) = redaction {
+                if !segment.is_empty() {
+                    segment.value = redaction.apply(&segment.value);
+                }
+            }
+        }
+    }
+
     /// Get module's name
     pub fn get_name(&self) -> &String {
         &self._name
@@ -128,27 +161,6 @@ impl<'a> Module<'a> {
         self.segments.iter().map(Segment::get_value).collect()
     }
 
-    /// Get the module's prefix
-    pub fn get_prefix(&mut self) -> &mut Affix {
-        &mut self.prefix
-    }
-
-    /// Get the module's suffix
-    pub fn get_suffix(&mut self) -> &mut Affix {
-        &mut self.suffix
-    }
-
-    /// Sets the style of the segment.
-    ///
-    /// Accepts either `Color` or `Style`.
-    pub fn set_style<T>(&mut self, style: T) -> &mut Module<'a>
-    where
-        T: Into<Style>,
-    {
-        self.style = style.into();
-        self
-    }
-
     /// Returns a vector of colored ANSIString elements to be later used with
     /// `ANSIStrings()` to optimize ANSI codes
     pub fn ansi_strings(&self) -> Vec<ANSIString> {
@@ -156,26 +168,18 @@ impl<'a> Module<'a> {
     }
 
     pub fn ansi_strings_for_shell(&self, shell: Shell) -> Vec<ANSIString> {
-        let mut ansi_strings = self
+        let ansi_strings = self
             .segments
             .iter()
             .map(Segment::ansi_string)
             .collect::<Vec<ANSIString>>();
 
-        ansi_strings.insert(0, self.prefix.ansi_string());
-        ansi_strings.push(self.suffix.ansi_string());
-
-        ansi_strings = match shell {
-            Shell::Bash => ansi_strings_modified(ansi_strings, shell),
-            Shell::Zsh => ansi_strings_modified(ansi_strings, shell),
+        match shell {
+            Shell::Bash | Shell::Tcsh | Shell::Xonsh | Shell::Zsh => {
+                ansi_strings_modified(ansi_strings, shell)
+            }
             _ => ansi_strings,
-        };
-
-        ansi_strings
-    }
-
-    pub fn to_string_without_prefix(&self, shell: Shell) -> String {
-        ANSIStrings(&self.ansi_strings_for_shell(shell)[1..]).to_string()
+        }
     }
 }
 
@@ -196,67 +200,6 @@ fn ansi_strings_modified(ansi_strings: Vec<ANSIString>, shell: Shell) -> Vec<ANS
         .collect::<Vec<ANSIString>>()
 }
 
-/// Module affixes are to be used for the prefix or suffix of a module.
-pub struct Affix {
-    /// The affix's name, to be used in configuration and logging.
-    _name: String,
-
-    /// The affix's style.
-    style: Style,
-
-    /// The string value of the affix.
-    value: String,
-}
-
-impl Affix {
-    pub fn default_prefix(name: &str) -> Self {
-        Self {
-            _name: format!("{}_prefix", name),
-            style: Style::default(),
-            value: "via ".to_string(),
-        }
-    }
-
-    pub fn default_suffix(name: &str) -> Self {
-        Self {
-            _name: format!("{}_suffix", name),
-            style: Style::default(),
-            value: " ".to_string(),
-        }
-    }
-
-    /// Sets the style of the module.
-    ///
-    /// Accepts either `Color` or `Style`.
-    pub fn set_style<T>(&mut self, style: T) -> &mut Self
-    where
-        T: Into<Style>,
-    {
-        self.style = style.into();
-        self
-    }
-
-    /// Sets the value of the module.
-    pub fn set_value<T>(&mut self, value: T) -> &mut Self
-    where
-        T: Into<String>,
-    {
-        self.value = value.into();
-        self
-    }
-
-    /// Generates the colored ANSIString output.
-    pub fn ansi_string(&self) -> ANSIString {
-        self.style.paint(&self.value)
-    }
-}
-
-impl fmt::Display for Affix {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.ansi_string())
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -269,10 +212,7 @@ mod tests {
             config: None,
             _name: name.to_string(),
             description: desc.to_string(),
-            style: Style::default(),
-            prefix: Affix::default_prefix(name),
             segments: Vec::new(),
-            suffix: Affix::default_suffix(name),
         };
 
         assert!(module.is_empty());
@@ -286,12 +226,44 @@ mod tests {
             config: None,
             _name: name.to_string(),
             description: desc.to_string(),
-            style: Style::default(),
-            prefix: Affix::default_prefix(name),
             segments: vec![Segment::new("test_segment")],
-            suffix: Affix::default_suffix(name),
         };
 
         assert!(module.is_empty());
     }
+
+    #[test]
+    fn test_redaction() {
+        assert_eq!(Redaction::Mask.apply("prod"), "***");
+
+        assert_eq!(Redaction::Hash.apply(""), "811c9dc5");
+        assert_eq!(Redaction::Hash.apply("a"), "e40c292c");
+        assert_eq!(Redaction::Hash.apply("foobar"), "bf9cf968");
+
+        let hash = Redaction::Hash.apply("host-1");
+        assert_eq!(hash.len(), 8);
+        assert_eq!(hash, Redaction::Hash.apply("host-1"));
+        assert_ne!(hash, Redaction::Hash.apply("host-2"));
+
+        let home = dirs_next::home_dir().unwrap();
+        let path = home.join("projects").to_string_lossy().to_string();
+        assert_eq!(
+            Redaction::Home.apply(&path),
+            format!("~{}projects", std::path::MAIN_SEPARATOR)
+        );
+    }
+
+    #[test]
+    fn test_module_redact() {
+        let mut module = Module::new("unit_test", "This is a unit test", None);
+        let mut text = Segment::new("_text");
+        text.set_value("on ");
+        let mut profile = Segment::new("profile");
+        profile.set_value("prod");
+        module.set_segments(vec![text, profile, Segment::new("region")]);
+
+        module.redact(&[("profile", Redaction::Mask), ("region", Redaction::Mask)]);
+
+        assert_eq!(module.get_segments(), vec!["on ", "***", ""]);
+    }
 }
@@ -5,9 +5,10 @@ use std::io::{BufRead, BufReader};
 use std::path::PathBuf;
 use std::str::FromStr;
 
-use super::{Context, Module, RootModuleConfig};
+use super::{Context, Module, Redaction, RootModuleConfig};
 
 use crate::configs::aws::{AwsConfig, AwsItems};
+use crate::formatter::StringFormatter;
 
 type Profile = String;
 type Region = String;
@@ -84,40 +85,54 @@ fn alias_region(region: &str, aliases: &HashMap<String, &str>) -> String {
     }
 }
 
-pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
-    const AWS_PREFIX: &str = "on ";
+/// The profile is masked when redaction is enabled, including in `all`
+pub const REDACTIONS: &[(&str, Redaction)] =
+    &[("profile", Redaction::Mask), ("all", Redaction::Mask)];
 
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("aws");
     let config: AwsConfig = AwsConfig::try_load(module.config);
 
-    module.set_style(config.style);
-
-    module.get_prefix().set_value(AWS_PREFIX);
-
-    module.create_segment("symbol", &config.symbol);
-    match config.displayed_items {
-        AwsItems::All => {
-            let (aws_profile, aws_region) = get_aws_profile_and_region();
-
-            let aws_segment = match (&aws_profile, &aws_region) {
-                (None, None) => return None,
-                (Some(p), Some(r)) => format!("{}({})", p, alias_region(r, &config.region_aliases)),
-                (Some(p), None) => p.to_string(),
-                (None, Some(r)) => alias_region(r, &config.region_aliases),
-            };
-            module.create_segment("all", &config.region.with_value(&aws_segment));
-        }
-        AwsItems::Profile => {
-            let aws_profile = env::var("AWS_PROFILE").ok()?;
+    let (aws_profile, aws_region) = match config.displayed_items {
+        AwsItems::All => get_aws_profile_and_region(),
+        AwsItems::Profile => (Some(env::var("AWS_PROFILE").ok()?), None),
+        AwsItems::Region => (None, Some(get_aws_region()?)),
+    };
+    let aws_region = aws_region.map(|region| alias_region(&region, &config.region_aliases));
 
-            module.create_segment("profile", &config.profile.with_value(&aws_profile));
-        }
-        AwsItems::Region => {
-            let aws_region = alias_region(&get_aws_region()?, &config.region_aliases);
+    let aws_all = match (&aws_profile, &aws_region) {
+        (None, None) => return None,
+        (Some(p), Some(r)) => format!("{}({})", p, r),
+        (Some(p), None) => p.to_string(),
+        (None, Some(r)) => r.to_string(),
+    };
 
-            module.create_segment("region", &config.region.with_value(&aws_region));
+    let parsed = StringFormatter::new(config.format).map(|formatter| {
+        formatter
+            .map_meta(|variable| match variable {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(config.style),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "all" => Some(aws_all.clone()),
+                "profile" => aws_profile.clone(),
+                "region" => aws_region.clone(),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `aws`:\n{}", error);
+            return None;
         }
-    };
+    });
 
     Some(module)
 }
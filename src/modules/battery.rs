@@ -1,5 +1,6 @@
 use super::{Context, Module, RootModuleConfig, Shell};
 use crate::configs::battery::BatteryConfig;
+use crate::formatter::StringFormatter;
 
 /// Creates a module for the battery percentage and charging state
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
@@ -14,61 +15,51 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let BatteryStatus { state, percentage } = battery_status;
 
     let mut module = context.new_module("battery");
-    let battery_config: BatteryConfig = BatteryConfig::try_load(module.config);
+    let config: BatteryConfig = BatteryConfig::try_load(module.config);
 
     // Parse config under `display`
-    let display_styles = &battery_config.display;
+    let display_styles = &config.display;
     let display_style = display_styles
         .iter()
-        .find(|display_style| percentage <= display_style.threshold as f32);
+        .find(|display_style| percentage <= display_style.threshold as f32)?;
 
-    if let Some(display_style) = display_style {
-        // Set style based on percentage
-        module.set_style(display_style.style);
-        module.get_prefix().set_value("");
+    // Parse the format string and build the module
+    let parsed = StringFormatter::new(config.format).map(|formatter| {
+        formatter
+            .map_meta(|variable| match variable {
+                "symbol" => match state {
+                    battery::State::Full => Some(config.full_symbol),
+                    battery::State::Charging => Some(config.charging_symbol),
+                    battery::State::Discharging => Some(config.discharging_symbol),
+                    battery::State::Unknown => Some(config.unknown_symbol),
+                    battery::State::Empty => Some(config.empty_symbol),
+                    _ => {
+                        log::debug!("Unhandled battery state `{}`", state);
+                        None
+                    }
+                },
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(display_style.style),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "percentage" => Some(format!("{}{}", percentage.round(), percentage_char)),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
 
-        match state {
-            battery::State::Full => {
-                module.create_segment("full_symbol", &battery_config.full_symbol);
-            }
-            battery::State::Charging => {
-                module.create_segment("charging_symbol", &battery_config.charging_symbol);
-            }
-            battery::State::Discharging => {
-                module.create_segment("discharging_symbol", &battery_config.discharging_symbol);
-            }
-            battery::State::Unknown => {
-                log::debug!("Unknown detected");
-                if let Some(unknown_symbol) = battery_config.unknown_symbol {
-                    module.create_segment("unknown_symbol", &unknown_symbol);
-                }
-            }
-            battery::State::Empty => {
-                if let Some(empty_symbol) = battery_config.empty_symbol {
-                    module.create_segment("empty_symbol", &empty_symbol);
-                }
-            }
-            _ => {
-                log::debug!("Unhandled battery state `{}`", state);
-                return None;
-            }
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `battery`:\n{}", error);
+            return None;
         }
+    });
 
-        let mut percent_string = Vec::<String>::with_capacity(2);
-        // Round the percentage to a whole number
-        percent_string.push(percentage.round().to_string());
-        percent_string.push(percentage_char.to_string());
-        module.create_segment(
-            "percentage",
-            &battery_config
-                .percentage
-                .with_value(percent_string.join("").as_ref()),
-        );
-
-        Some(module)
-    } else {
-        None
-    }
+    Some(module)
 }
 
 fn get_battery_status() -> Option<BatteryStatus> {
@@ -1,25 +1,25 @@
 use super::{Context, Module, RootModuleConfig, Shell};
 use crate::configs::character::CharacterConfig;
+use crate::formatter::StringFormatter;
 
 /// Creates a module for the prompt character
 ///
 /// The character segment prints an arrow character in a color dependant on the exit-
 /// code of the last executed command:
-/// - If the exit-code was "0", the arrow will be formatted with `style_success`
-/// (green by default)
-/// - If the exit-code was anything else, the arrow will be formatted with
-/// `style_failure` (red by default)
+/// - If the exit-code was "0", it will be formatted with `success_symbol`
+///   (green arrow by default)
+/// - If the exit-code was anything else, it will be formatted with
+///   `error_symbol` (red arrow by default)
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     enum ShellEditMode {
         Normal,
         Insert,
-    };
+    }
     const ASSUMED_MODE: ShellEditMode = ShellEditMode::Insert;
     // TODO: extend config to more modes
 
     let mut module = context.new_module("character");
     let config: CharacterConfig = CharacterConfig::try_load(module.config);
-    module.get_prefix().set_value("");
 
     let props = &context.properties;
     let exit_code_default = std::string::String::from("0");
@@ -34,26 +34,39 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     // We do some environment detection in src/init.rs to translate.
     // The result: in non-vi fish, keymap is always reported as "insert"
     let mode = match (&context.shell, keymap.as_str()) {
-        (Shell::Fish, "default") | (Shell::Zsh, "vicmd") => ShellEditMode::Normal,
+        (Shell::Fish, "default") | (Shell::Zsh, "vicmd") | (Shell::Xonsh, "vicmd") => {
+            ShellEditMode::Normal
+        }
         _ => ASSUMED_MODE,
     };
 
-    if exit_success {
-        module.set_style(config.style_success);
-    } else {
-        module.set_style(config.style_failure);
+    let symbol = match mode {
+        ShellEditMode::Normal => config.vicmd_symbol,
+        ShellEditMode::Insert => {
+            if exit_success {
+                config.success_symbol
+            } else {
+                config.error_symbol
+            }
+        }
     };
 
-    /* If an error symbol is set in the config, use symbols to indicate
-    success/failure, in addition to color */
-    if config.use_symbol_for_status && !exit_success {
-        module.create_segment("error_symbol", &config.error_symbol)
-    } else {
-        match mode {
-            ShellEditMode::Normal => module.create_segment("vicmd_symbol", &config.vicmd_symbol),
-            ShellEditMode::Insert => module.create_segment("symbol", &config.symbol),
+    let parsed = StringFormatter::new(config.format).map(|formatter| {
+        formatter
+            .map_meta(|variable| match variable {
+                "symbol" => Some(symbol),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `character`:\n{}", error);
+            return None;
         }
-    };
+    });
 
     Some(module)
 }
@@ -5,6 +5,7 @@ use super::{Context, Module};
 use super::utils::directory::truncate;
 use crate::config::RootModuleConfig;
 use crate::configs::conda::CondaConfig;
+use crate::formatter::StringFormatter;
 
 /// Creates a module with the current Conda environment
 ///
@@ -21,10 +22,30 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
 
     let conda_env = truncate(conda_env, config.truncation_length);
 
-    module.set_style(config.style);
-
-    module.create_segment("symbol", &config.symbol);
-    module.create_segment("environment", &config.environment.with_value(&conda_env));
+    let parsed = StringFormatter::new(config.format).map(|formatter| {
+        formatter
+            .map_meta(|variable| match variable {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(config.style),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "environment" => Some(conda_env.clone()),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `conda`:\n{}", error);
+            return None;
+        }
+    });
 
     Some(module)
 }
@@ -1,10 +1,12 @@
-use ansi_term::Color;
 use std::io::Write;
 use std::process::{Command, Output, Stdio};
 
-use super::{Context, Module, RootModuleConfig};
+use super::{Context, Module, Redaction, RootModuleConfig};
 
-use crate::{config::SegmentConfig, configs::custom::CustomConfig};
+use crate::{configs::custom::CustomConfig, formatter::StringFormatter};
+
+/// The output of the command is hidden when redaction is enabled, as it can show anything
+pub const REDACTIONS: &[(&str, Redaction)] = &[("output", Redaction::Mask)];
 
 /// Creates a custom module with some configuration
 ///
@@ -13,7 +15,7 @@ use crate::{config::SegmentConfig, configs::custom::CustomConfig};
 /// command can be run -- if its result is 0, the module will be shown.
 ///
 /// Finally, the content of the module itself is also set by a command.
-pub fn module<'a>(name: &'a str, context: &'a Context) -> Option<Module<'a>> {
+pub fn module<'a>(name: &str, context: &'a Context) -> Option<Module<'a>> {
     let toml_config = context.config.get_custom_module_config(name).expect(
         "modules::custom::module should only be called after ensuring that the module exists",
     );
@@ -44,35 +46,40 @@ pub fn module<'a>(name: &'a str, context: &'a Context) -> Option<Module<'a>> {
     }
 
     let mut module = Module::new(name, config.description, Some(toml_config));
-    let style = config.style.unwrap_or_else(|| Color::Green.bold());
 
-    if let Some(prefix) = config.prefix {
-        module.get_prefix().set_value(prefix);
-    }
-    if let Some(suffix) = config.suffix {
-        module.get_suffix().set_value(suffix);
-    }
+    let output = exec_command(config.command, &config.shell.0)?;
+    let trimmed = output.trim();
 
-    if let Some(symbol) = config.symbol {
-        module.create_segment("symbol", &symbol);
+    if trimmed.is_empty() {
+        return None;
     }
 
-    if let Some(output) = exec_command(config.command, &config.shell.0) {
-        let trimmed = output.trim();
-
-        if trimmed.is_empty() {
+    let parsed = StringFormatter::new(config.format).map(|formatter| {
+        formatter
+            .map_meta(|variable| match variable {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(config.style),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "output" => Some(trimmed.to_string()),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `custom.{}`:\n{}", name, error);
             return None;
         }
+    });
 
-        module.create_segment(
-            "output",
-            &SegmentConfig::new(&trimmed).with_style(Some(style)),
-        );
-
-        Some(module)
-    } else {
-        None
-    }
+    Some(module)
 }
 
 /// Return the invoking shell, using `shell` and fallbacking in order to STARSHIP_SHELL and "sh"
@@ -4,11 +4,15 @@ use std::iter::FromIterator;
 use std::path::{Path, PathBuf};
 use unicode_segmentation::UnicodeSegmentation;
 
-use super::{Context, Module};
+use super::{Context, Module, Redaction};
 
 use super::utils::directory::truncate;
-use crate::config::{RootModuleConfig, SegmentConfig};
+use crate::config::RootModuleConfig;
 use crate::configs::directory::DirectoryConfig;
+use crate::formatter::StringFormatter;
+
+/// The home directory is hidden from the path when redaction is enabled
+pub const REDACTIONS: &[(&str, Redaction)] = &[("path", Redaction::Home)];
 
 /// Creates a module with the current directory
 ///
@@ -27,9 +31,13 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     const HOME_SYMBOL: &str = "~";
 
     let mut module = context.new_module("directory");
-    let config: DirectoryConfig = DirectoryConfig::try_load(module.config);
+    let mut config: DirectoryConfig = DirectoryConfig::try_load(module.config);
 
-    module.set_style(config.style);
+    // The short form keeps the last directory and abbreviates the others, fish-style
+    if context.shortened.contains("directory") {
+        config.truncation_length = 1;
+        config.fish_style_pwd_dir_length = config.fish_style_pwd_dir_length.max(1);
+    }
 
     // Using environment PWD is the standard approach for determining logical path
     // If this is None for any reason, we fall back to reading the os-provided path
@@ -80,33 +88,39 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
 
     // Substitutions could have changed the prefix, so don't allow them and
     // fish-style path contraction together
-    if config.fish_style_pwd_dir_length > 0 && config.substitutions.is_empty() {
+    let fish_prefix = if config.fish_style_pwd_dir_length > 0 && config.substitutions.is_empty() {
         // If user is using fish style path, we need to add the segment first
         let contracted_home_dir = contract_path(&current_dir, &home_dir, HOME_SYMBOL);
-        let fish_style_dir = to_fish_style(
+        to_fish_style(
             config.fish_style_pwd_dir_length as usize,
             contracted_home_dir,
             &truncated_dir_string,
-        );
-
-        module.create_segment(
-            "path",
-            &SegmentConfig {
-                value: &fish_style_dir,
-                style: None,
-            },
-        );
-    }
-
-    module.create_segment(
-        "path",
-        &SegmentConfig {
-            value: &truncated_dir_string,
-            style: None,
-        },
-    );
-
-    module.get_prefix().set_value(config.prefix);
+        )
+    } else {
+        String::from("")
+    };
+    let final_dir_string = format!("{}{}", fish_prefix, truncated_dir_string);
+
+    let parsed = StringFormatter::new(config.format).map(|formatter| {
+        formatter
+            .map_style(|variable| match variable {
+                "style" => Some(config.style),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "path" => Some(final_dir_string.clone()),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `directory`:\n{}", error);
+            return None;
+        }
+    });
 
     Some(module)
 }
@@ -1,10 +1,14 @@
-use super::{Context, Module, RootModuleConfig};
+use super::{Context, Module, Redaction, RootModuleConfig};
 
 use crate::configs::docker_context::DockerContextConfig;
+use crate::formatter::StringFormatter;
 use crate::utils;
 
 const DOCKER_CONFIG_FILE: &str = ".docker/config.json";
 
+/// The context is masked when redaction is enabled
+pub const REDACTIONS: &[(&str, Redaction)] = &[("context", Redaction::Mask)];
+
 /// Creates a module with the currently active Docker context
 ///
 /// Will display the Docker context if the following criteria are met:
@@ -28,19 +32,38 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let json = utils::read_file(config_path).ok()?;
     let parsed_json = serde_json::from_str(&json).ok()?;
 
-    match parsed_json {
-        serde_json::Value::Object(root) => {
-            let current_context = root.get("currentContext")?;
-            match current_context {
-                serde_json::Value::String(ctx) => {
-                    module.set_style(config.style);
-                    module.create_segment("symbol", &config.symbol);
-                    module.create_segment("context", &config.context.with_value(&ctx));
-                    Some(module)
-                }
+    let current_context = match parsed_json {
+        serde_json::Value::Object(root) => match root.get("currentContext")? {
+            serde_json::Value::String(ctx) => ctx.to_owned(),
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    let parsed = StringFormatter::new(config.format).map(|formatter| {
+        formatter
+            .map_meta(|variable| match variable {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(config.style),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "context" => Some(current_context.clone()),
                 _ => None,
-            }
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `docker_context`:\n{}", error);
+            return None;
         }
-        _ => None,
-    }
+    });
+
+    Some(module)
 }
@@ -3,6 +3,7 @@ use regex::Regex;
 use super::{Context, Module, RootModuleConfig};
 
 use crate::configs::elixir::ElixirConfig;
+use crate::formatter::StringFormatter;
 
 const ELIXIR_VERSION_PATTERN: &str = "\
 Erlang/OTP (?P<otp>\\d+)[^\\n]+
@@ -24,16 +25,32 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
 
     let mut module = context.new_module("elixir");
     let config = ElixirConfig::try_load(module.config);
-    module.set_style(config.style);
-
-    module.create_segment("symbol", &config.symbol);
-    module.create_segment("version", &config.version.with_value(&elixir_version));
-    module.create_segment(
-        "otp_version",
-        &config
-            .otp_version
-            .with_value(&format!(" (OTP {})", otp_version)),
-    );
+
+    let parsed = StringFormatter::new(config.format).map(|formatter| {
+        formatter
+            .map_meta(|variable| match variable {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(config.style),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "version" => Some(elixir_version.clone()),
+                "otp_version" => Some(otp_version.clone()),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `elixir`:\n{}", error);
+            return None;
+        }
+    });
 
     Some(module)
 }
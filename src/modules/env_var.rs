@@ -1,9 +1,13 @@
 use std::env;
 
-use super::{Context, Module, SegmentConfig};
+use super::{Context, Module, Redaction};
 
 use crate::config::RootModuleConfig;
 use crate::configs::env_var::EnvVarConfig;
+use crate::formatter::StringFormatter;
+
+/// The value of the variable is masked when redaction is enabled
+pub const REDACTIONS: &[(&str, Redaction)] = &[("env_value", Redaction::Mask)];
 
 /// Creates a module with the value of the chosen environment variable
 ///
@@ -17,16 +21,30 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
 
     let env_value = get_env_value(config.variable?, config.default)?;
 
-    module.set_style(config.style);
-    module.get_prefix().set_value("with ");
-
-    if let Some(symbol) = config.symbol {
-        module.create_segment("symbol", &symbol);
-    }
-
-    // TODO: Use native prefix and suffix instead of stacking custom ones together with env_value.
-    let env_var_stacked = format!("{}{}{}", config.prefix, env_value, config.suffix);
-    module.create_segment("env_var", &SegmentConfig::new(&env_var_stacked));
+    let parsed = StringFormatter::new(config.format).map(|formatter| {
+        formatter
+            .map_meta(|variable| match variable {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(config.style),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "env_value" => Some(env_value.clone()),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `env_var`:\n{}", error);
+            return None;
+        }
+    });
 
     Some(module)
 }
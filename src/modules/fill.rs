@@ -0,0 +1,26 @@
+use super::{Context, Module, RootModuleConfig};
+
+use crate::config::parse_style_string;
+use crate::configs::fill::FillConfig;
+use crate::segment::Segment;
+
+/// Name of the segment of the fill module, expanded once the rest of its line is known
+pub const FILL_SEGMENT: &str = "_fill";
+
+/// Creates a module filling the remaining columns of its line with its symbol
+///
+/// The module only holds the symbol to repeat: the segment is expanded to its width when
+/// the whole prompt is rendered.
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("fill");
+    let config: FillConfig = FillConfig::try_load(module.config);
+
+    let mut segment = Segment::new(FILL_SEGMENT);
+    segment.set_value(config.symbol);
+    if let Some(style) = parse_style_string(config.style, Some(context)) {
+        segment.set_style(style);
+    }
+    module.set_segments(vec![segment]);
+
+    Some(module)
+}
@@ -1,21 +1,31 @@
+use git2::{Repository, Status, StatusOptions};
+use std::path::Path;
 use unicode_segmentation::UnicodeSegmentation;
 
 use super::{Context, Module, RootModuleConfig};
 
 use crate::configs::git_branch::GitBranchConfig;
+use crate::formatter::StringFormatter;
+
+/// The length of the branch in the short form of the module
+const SHORT_BRANCH_LENGTH: i64 = 8;
 
 /// Creates a module with the Git branch in the current directory
 ///
 /// Will display the branch name if the current directory is a git repo
+///
+/// The `$branch_style` variable can be used in a style string to color the branch
+/// depending on whether the working tree is clean or dirty.
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("git_branch");
-    let config = GitBranchConfig::try_load(module.config);
-    module.set_style(config.style);
+    let mut config = GitBranchConfig::try_load(module.config);
 
-    module.get_prefix().set_value("on ");
+    // The short form keeps the first characters of the branch
+    if context.shortened.contains("git_branch") {
+        config.truncation_length = config.truncation_length.min(SHORT_BRANCH_LENGTH);
+    }
 
     let truncation_symbol = get_graphemes(config.truncation_symbol, 1);
-    module.create_segment("symbol", &config.symbol);
 
     // TODO: Once error handling is implemented, warn the user if their config
     // truncation length is nonsensical
@@ -39,14 +49,69 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         truncated_graphemes
     };
 
-    module.create_segment(
-        "name",
-        &config.branch_name.with_value(&truncated_and_symbol),
-    );
+    let parsed = StringFormatter::new(config.format).map(|formatter| {
+        formatter
+            .map_meta(|variable| match variable {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(config.style),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "branch" => Some(truncated_and_symbol.clone()),
+                "branch_style" => {
+                    let branch_style = match repo.root.as_deref() {
+                        Some(repo_root) if is_dirty(repo_root) => config.dirty_style,
+                        _ => config.clean_style,
+                    };
+                    Some(branch_style.to_string())
+                }
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `git_branch`:\n{}", error);
+            return None;
+        }
+    });
 
     Some(module)
 }
 
+/// Checks whether the working tree or the index of the repository has any changes
+fn is_dirty(repo_root: &Path) -> bool {
+    let repository = match Repository::open(repo_root) {
+        Ok(repository) => repository,
+        Err(error) => {
+            log::debug!("Failed to open repository: {}", error);
+            return false;
+        }
+    };
+
+    let mut status_options = StatusOptions::new();
+    status_options
+        .include_untracked(true)
+        .exclude_submodules(true);
+
+    let statuses = match repository.statuses(Some(&mut status_options)) {
+        Ok(statuses) => statuses,
+        Err(error) => {
+            log::debug!("Failed to get repository status: {}", error);
+            return false;
+        }
+    };
+
+    statuses
+        .iter()
+        .any(|entry| !entry.status().intersects(Status::CURRENT | Status::IGNORED))
+}
+
 fn get_graphemes(text: &str, length: usize) -> String {
     UnicodeSegmentation::graphemes(text, true)
         .take(length)
@@ -2,6 +2,7 @@ use super::{Context, Module, RootModuleConfig};
 use git2::Repository;
 
 use crate::configs::git_commit::GitCommitConfig;
+use crate::formatter::StringFormatter;
 
 /// Creates a module with the Git commit in the current directory
 ///
@@ -10,16 +11,6 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("git_commit");
     let config = GitCommitConfig::try_load(module.config);
 
-    module
-        .get_prefix()
-        .set_value(config.prefix)
-        .set_style(config.style);
-    module
-        .get_suffix()
-        .set_value(config.suffix)
-        .set_style(config.style);
-    module.set_style(config.style);
-
     let repo = context.get_repo().ok()?;
     let repo_root = repo.root.as_ref()?;
     let git_repo = Repository::open(repo_root).ok()?;
@@ -32,13 +23,29 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let git_head = git_repo.head().ok()?;
     let head_commit = git_head.peel_to_commit().ok()?;
     let commit_oid = head_commit.id();
-    module.create_segment(
-        "hash",
-        &config.hash.with_value(&id_to_hex_abbrev(
-            commit_oid.as_bytes(),
-            config.commit_hash_length,
-        )),
-    );
+    let parsed = StringFormatter::new(config.format).map(|formatter| {
+        formatter
+            .map_style(|variable| match variable {
+                "style" => Some(config.style),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "hash" => Some(id_to_hex_abbrev(
+                    commit_oid.as_bytes(),
+                    config.commit_hash_length,
+                )),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `git_commit`:\n{}", error);
+            return None;
+        }
+    });
 
     Some(module)
 }
@@ -1,8 +1,9 @@
 use git2::RepositoryState;
-use std::path::{Path, PathBuf};
+use std::path::Path;
 
-use super::{Context, Module, RootModuleConfig, SegmentConfig};
+use super::{Context, Module, RootModuleConfig};
 use crate::configs::git_state::GitStateConfig;
+use crate::formatter::StringFormatter;
 
 /// Creates a module with the state of the git repository at the current directory
 ///
@@ -12,37 +13,50 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("git_state");
     let config: GitStateConfig = GitStateConfig::try_load(module.config);
 
-    module.set_style(config.style);
-    module.get_prefix().set_value("(");
-    module.get_suffix().set_value(") ");
-
     let repo = context.get_repo().ok()?;
     let repo_root = repo.root.as_ref()?;
     let repo_state = repo.state?;
 
-    let state_description = get_state_description(repo_state, repo_root, config);
+    let state_description = get_state_description(repo_state, repo_root, &config);
 
-    let label = match &state_description {
-        StateDescription::Label(label) => label,
-        StateDescription::LabelAndProgress(label, _) => label,
+    let (label, progress) = match state_description {
+        StateDescription::Label(label) => (label, None),
+        StateDescription::LabelAndProgress(label, progress) => (label, Some(progress)),
         StateDescription::Clean => {
             return None;
         }
     };
 
-    module.create_segment(label.name, &label.segment);
-
-    if let StateDescription::LabelAndProgress(_, progress) = &state_description {
-        module.create_segment(
-            "progress_current",
-            &SegmentConfig::new(&format!(" {}", progress.current)),
-        );
-        module.create_segment("progress_divider", &SegmentConfig::new("/"));
-        module.create_segment(
-            "progress_total",
-            &SegmentConfig::new(&format!("{}", progress.total)),
-        );
-    }
+    let parsed = StringFormatter::new(config.format).map(|formatter| {
+        formatter
+            .map_style(|variable| match variable {
+                "style" => Some(config.style),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "state" => Some(label.to_string()),
+                "progress" => Some(progress.as_ref().map_or_else(String::new, |progress| {
+                    format!(
+                        " {}{}{}",
+                        progress.current, config.progress_divider, progress.total
+                    )
+                })),
+                "progress_current" => progress
+                    .as_ref()
+                    .map(|progress| progress.current.to_string()),
+                "progress_total" => progress.as_ref().map(|progress| progress.total.to_string()),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `git_state`:\n{}", error);
+            return None;
+        }
+    });
 
     Some(module)
 }
@@ -52,41 +66,26 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
 /// During a git operation it will show: REBASING, BISECTING, MERGING, etc.
 fn get_state_description<'a>(
     state: RepositoryState,
-    root: &'a std::path::PathBuf,
-    config: GitStateConfig<'a>,
+    root: &'a Path,
+    config: &GitStateConfig<'a>,
 ) -> StateDescription<'a> {
     match state {
         RepositoryState::Clean => StateDescription::Clean,
-        RepositoryState::Merge => StateDescription::Label(StateLabel::new("merge", config.merge)),
-        RepositoryState::Revert => {
-            StateDescription::Label(StateLabel::new("revert", config.revert))
-        }
-        RepositoryState::RevertSequence => {
-            StateDescription::Label(StateLabel::new("revert", config.revert))
-        }
-        RepositoryState::CherryPick => {
-            StateDescription::Label(StateLabel::new("cherry_pick", config.cherry_pick))
-        }
-        RepositoryState::CherryPickSequence => {
-            StateDescription::Label(StateLabel::new("cherry_pick", config.cherry_pick))
-        }
-        RepositoryState::Bisect => {
-            StateDescription::Label(StateLabel::new("bisect", config.bisect))
-        }
-        RepositoryState::ApplyMailbox => StateDescription::Label(StateLabel::new("am", config.am)),
-        RepositoryState::ApplyMailboxOrRebase => {
-            StateDescription::Label(StateLabel::new("am_or_rebase", config.am_or_rebase))
-        }
+        RepositoryState::Merge => StateDescription::Label(config.merge),
+        RepositoryState::Revert => StateDescription::Label(config.revert),
+        RepositoryState::RevertSequence => StateDescription::Label(config.revert),
+        RepositoryState::CherryPick => StateDescription::Label(config.cherry_pick),
+        RepositoryState::CherryPickSequence => StateDescription::Label(config.cherry_pick),
+        RepositoryState::Bisect => StateDescription::Label(config.bisect),
+        RepositoryState::ApplyMailbox => StateDescription::Label(config.am),
+        RepositoryState::ApplyMailboxOrRebase => StateDescription::Label(config.am_or_rebase),
         RepositoryState::Rebase => describe_rebase(root, config.rebase),
         RepositoryState::RebaseInteractive => describe_rebase(root, config.rebase),
         RepositoryState::RebaseMerge => describe_rebase(root, config.rebase),
     }
 }
 
-fn describe_rebase<'a>(
-    root: &'a PathBuf,
-    rebase_config: SegmentConfig<'a>,
-) -> StateDescription<'a> {
+fn describe_rebase<'a>(root: &'a Path, rebase_config: &'a str) -> StateDescription<'a> {
     /*
      *  Sadly, libgit2 seems to have some issues with reading the state of
      *  interactive rebases. So, instead, we'll poke a few of the .git files
@@ -124,31 +123,18 @@ fn describe_rebase<'a>(
     };
 
     match progress {
-        None => StateDescription::Label(StateLabel::new("rebase", rebase_config)),
-        Some(progress) => {
-            StateDescription::LabelAndProgress(StateLabel::new("rebase", rebase_config), progress)
-        }
+        None => StateDescription::Label(rebase_config),
+        Some(progress) => StateDescription::LabelAndProgress(rebase_config, progress),
     }
 }
 
 enum StateDescription<'a> {
     Clean,
-    Label(StateLabel<'a>),
-    LabelAndProgress(StateLabel<'a>, StateProgress),
-}
-
-struct StateLabel<'a> {
-    name: &'static str,
-    segment: SegmentConfig<'a>,
+    Label(&'a str),
+    LabelAndProgress(&'a str, StateProgress),
 }
 
 struct StateProgress {
     current: usize,
     total: usize,
 }
-
-impl<'a> StateLabel<'a> {
-    fn new(name: &'static str, segment: SegmentConfig<'a>) -> Self {
-        Self { name, segment }
-    }
-}
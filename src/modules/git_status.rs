@@ -2,11 +2,14 @@ use git2::{Repository, Status};
 
 use super::{Context, Module, RootModuleConfig};
 
-use crate::config::SegmentConfig;
-use crate::configs::git_status::{CountConfig, GitStatusConfig};
+use crate::configs::git_status::GitStatusConfig;
+use crate::formatter::StringFormatter;
+use crate::segment::Segment;
 use std::borrow::BorrowMut;
 use std::collections::HashMap;
 
+const ALL_STATUS_FORMAT: &str = "$conflicted$stashed$deleted$renamed$modified$staged$untracked";
+
 /// Creates a module with the Git branch in the current directory
 ///
 /// Will display the branch name if the current directory is a git repo
@@ -30,18 +33,9 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("git_status");
     let config: GitStatusConfig = GitStatusConfig::try_load(module.config);
 
-    module
-        .get_prefix()
-        .set_value(config.prefix)
-        .set_style(config.style);
-    module
-        .get_suffix()
-        .set_value(config.suffix)
-        .set_style(config.style);
-    module.set_style(config.style);
-
     let repo_status = get_repo_status(repository.borrow_mut());
     log::debug!("Repo status: {:?}", repo_status);
+    let repo_status = repo_status.unwrap_or_default();
 
     let ahead_behind = get_ahead_behind(&repository, branch_name);
     if ahead_behind == Ok((0, 0)) {
@@ -49,142 +43,139 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     } else {
         log::debug!("Repo ahead/behind: {:?}", ahead_behind);
     }
+    let (ahead, behind) = ahead_behind.unwrap_or((0, 0));
 
-    // Add the conflicted segment
-    if let Ok(repo_status) = repo_status {
-        create_segment_with_count(
-            &mut module,
-            "conflicted",
-            repo_status.conflicted,
-            &config.conflicted,
-            config.conflicted_count,
-        );
+    if repo_status.is_clean() && ahead == 0 && behind == 0 {
+        return None;
     }
 
-    // Add the ahead/behind segment
-    if let Ok((ahead, behind)) = ahead_behind {
-        let add_ahead = |m: &mut Module<'a>| {
-            create_segment_with_count(
-                m,
-                "ahead",
-                ahead,
-                &config.ahead,
-                CountConfig {
-                    enabled: config.show_sync_count,
-                    style: None,
-                },
-            );
-        };
-
-        let add_behind = |m: &mut Module<'a>| {
-            create_segment_with_count(
-                m,
-                "behind",
-                behind,
-                &config.behind,
-                CountConfig {
-                    enabled: config.show_sync_count,
-                    style: None,
-                },
-            );
-        };
-
-        if ahead > 0 && behind > 0 {
-            module.create_segment("diverged", &config.diverged);
-
-            if config.show_sync_count {
-                add_ahead(&mut module);
-                add_behind(&mut module);
-            }
-        }
-
-        if ahead > 0 && behind == 0 {
-            add_ahead(&mut module);
+    let parsed = StringFormatter::new(config.format).map(|formatter| {
+        formatter
+            .map_meta(|variable| match variable {
+                "all_status" => Some(ALL_STATUS_FORMAT),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(config.style),
+                _ => None,
+            })
+            .map_variables_to_segments(|variable| match variable {
+                "ahead_behind" => {
+                    if ahead > 0 && behind > 0 {
+                        format_text(
+                            config.diverged,
+                            "git_status.diverged",
+                            context,
+                            |variable| match variable {
+                                "ahead_count" => Some(ahead.to_string()),
+                                "behind_count" => Some(behind.to_string()),
+                                _ => None,
+                            },
+                        )
+                    } else if ahead > 0 {
+                        format_count(config.ahead, "git_status.ahead", context, ahead)
+                    } else if behind > 0 {
+                        format_count(config.behind, "git_status.behind", context, behind)
+                    } else {
+                        None
+                    }
+                }
+                "conflicted" => format_count(
+                    config.conflicted,
+                    "git_status.conflicted",
+                    context,
+                    repo_status.conflicted,
+                ),
+                "stashed" => format_count(
+                    config.stashed,
+                    "git_status.stashed",
+                    context,
+                    repo_status.stashed,
+                ),
+                "deleted" => format_count(
+                    config.deleted,
+                    "git_status.deleted",
+                    context,
+                    repo_status.deleted,
+                ),
+                "renamed" => format_count(
+                    config.renamed,
+                    "git_status.renamed",
+                    context,
+                    repo_status.renamed,
+                ),
+                "modified" => format_count(
+                    config.modified,
+                    "git_status.modified",
+                    context,
+                    repo_status.modified,
+                ),
+                "staged" => format_count(
+                    config.staged,
+                    "git_status.staged",
+                    context,
+                    repo_status.staged,
+                ),
+                "untracked" => format_count(
+                    config.untracked,
+                    "git_status.untracked",
+                    context,
+                    repo_status.untracked,
+                ),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `git_status`:\n{}", error);
+            return None;
         }
+    });
 
-        if behind > 0 && ahead == 0 {
-            add_behind(&mut module);
-        }
-    }
-
-    // Add the stashed segment
-    if let Ok(repo_status) = repo_status {
-        create_segment_with_count(
-            &mut module,
-            "stashed",
-            repo_status.stashed,
-            &config.stashed,
-            config.stashed_count,
-        );
-    }
-
-    // Add all remaining status segments
-    if let Ok(repo_status) = repo_status {
-        create_segment_with_count(
-            &mut module,
-            "deleted",
-            repo_status.deleted,
-            &config.deleted,
-            config.deleted_count,
-        );
-
-        create_segment_with_count(
-            &mut module,
-            "renamed",
-            repo_status.renamed,
-            &config.renamed,
-            config.renamed_count,
-        );
-
-        create_segment_with_count(
-            &mut module,
-            "modified",
-            repo_status.modified,
-            &config.modified,
-            config.modified_count,
-        );
-
-        create_segment_with_count(
-            &mut module,
-            "staged",
-            repo_status.staged,
-            &config.staged,
-            config.staged_count,
-        );
-
-        create_segment_with_count(
-            &mut module,
-            "untracked",
-            repo_status.untracked,
-            &config.untracked,
-            config.untracked_count,
-        );
-    }
+    Some(module)
+}
 
-    if module.is_empty() {
-        return None;
+/// Formats a symbol string, mapping its variables with the given mapper
+fn format_text<F>(
+    format_str: &str,
+    config_path: &str,
+    context: &Context,
+    mapper: F,
+) -> Option<Vec<Segment>>
+where
+    F: Fn(&str) -> Option<String> + Sync,
+{
+    if let Ok(formatter) = StringFormatter::new(format_str) {
+        Some(formatter.map(mapper).parse(None, Some(context)))
+    } else {
+        log::warn!("Error parsing format string `{}`", config_path);
+        None
     }
-
-    Some(module)
 }
 
-fn create_segment_with_count<'a>(
-    module: &mut Module<'a>,
-    name: &str,
+/// Formats a symbol string with `$count`, or returns None if count is zero
+fn format_count(
+    format_str: &str,
+    config_path: &str,
+    context: &Context,
     count: usize,
-    config: &SegmentConfig<'a>,
-    count_config: CountConfig,
-) {
-    if count > 0 {
-        module.create_segment(name, &config);
-
-        if count_config.enabled {
-            module.create_segment(
-                &format!("{}_count", name),
-                &SegmentConfig::new(&count.to_string()).with_style(count_config.style),
-            );
-        }
+) -> Option<Vec<Segment>> {
+    if count == 0 {
+        return None;
     }
+
+    format_text(
+        format_str,
+        config_path,
+        context,
+        |variable| match variable {
+            "count" => Some(count.to_string()),
+            _ => None,
+        },
+    )
 }
 
 /// Gets the number of files in various git states (staged, modified, deleted, etc...)
@@ -305,3 +296,15 @@ struct RepoStatus {
     untracked: usize,
     stashed: usize,
 }
+
+impl RepoStatus {
+    fn is_clean(&self) -> bool {
+        self.conflicted == 0
+            && self.deleted == 0
+            && self.renamed == 0
+            && self.modified == 0
+            && self.staged == 0
+            && self.untracked == 0
+            && self.stashed == 0
+    }
+}
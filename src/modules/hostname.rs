@@ -1,10 +1,15 @@
 use std::env;
 
-use super::{Context, Module, SegmentConfig};
+use super::{Context, Module, Redaction};
 use std::ffi::OsString;
 
 use crate::config::RootModuleConfig;
 use crate::configs::hostname::HostnameConfig;
+use crate::formatter::StringFormatter;
+
+/// The hostname is replaced by a hash when redaction is enabled, so that machines can still
+/// be told apart
+pub const REDACTIONS: &[(&str, Redaction)] = &[("hostname", Redaction::Hash)];
 
 /// Creates a module with the system hostname
 ///
@@ -42,10 +47,26 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         host.as_ref()
     };
 
-    module.set_style(config.style);
-    let hostname_stacked = format!("{}{}{}", config.prefix, host, config.suffix);
-    module.create_segment("hostname", &SegmentConfig::new(&hostname_stacked));
-    module.get_prefix().set_value("on ");
+    let parsed = StringFormatter::new(config.format).map(|formatter| {
+        formatter
+            .map_style(|variable| match variable {
+                "style" => Some(config.style),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "hostname" => Some(host.to_string()),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `hostname`:\n{}", error);
+            return None;
+        }
+    });
 
     Some(module)
 }
@@ -1,6 +1,7 @@
 use crate::configs::java::JavaConfig;
+use crate::formatter::StringFormatter;
 
-use super::{Context, Module, RootModuleConfig, SegmentConfig};
+use super::{Context, Module, RootModuleConfig};
 
 use crate::modules::utils::java_version_parser;
 use crate::utils;
@@ -25,11 +26,33 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         Some(java_version) => {
             let mut module = context.new_module("java");
             let config: JavaConfig = JavaConfig::try_load(module.config);
-            module.set_style(config.style);
 
             let formatted_version = format_java_version(java_version)?;
-            module.create_segment("symbol", &config.symbol);
-            module.create_segment("version", &SegmentConfig::new(&formatted_version));
+
+            let parsed = StringFormatter::new(config.format).map(|formatter| {
+                formatter
+                    .map_meta(|variable| match variable {
+                        "symbol" => Some(config.symbol),
+                        _ => None,
+                    })
+                    .map_style(|variable| match variable {
+                        "style" => Some(config.style),
+                        _ => None,
+                    })
+                    .map(|variable| match variable {
+                        "version" => Some(formatted_version.clone()),
+                        _ => None,
+                    })
+                    .parse(None, Some(context))
+            });
+
+            module.set_segments(match parsed {
+                Ok(segments) => segments,
+                Err(error) => {
+                    log::warn!("Error in module `java`:\n{}", error);
+                    return None;
+                }
+            });
 
             Some(module)
         }
@@ -1,15 +1,14 @@
 use super::{Context, Module};
 
-use crate::config::{RootModuleConfig, SegmentConfig};
+use crate::config::RootModuleConfig;
 use crate::configs::jobs::JobsConfig;
+use crate::formatter::StringFormatter;
 
 /// Creates a segment to show if there are any active jobs running
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("jobs");
     let config: JobsConfig = JobsConfig::try_load(module.config);
 
-    module.set_style(config.style);
-
     let props = &context.properties;
     let num_of_jobs = props
         .get("jobs")
@@ -20,11 +19,37 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     if num_of_jobs == 0 {
         return None;
     }
-    module.create_segment("symbol", &config.symbol);
-    if num_of_jobs > config.threshold {
-        module.create_segment("number", &SegmentConfig::new(&num_of_jobs.to_string()));
-    }
-    module.get_prefix().set_value("");
+
+    let module_number = if num_of_jobs > config.threshold {
+        num_of_jobs.to_string()
+    } else {
+        "".to_string()
+    };
+
+    let parsed = StringFormatter::new(config.format).map(|formatter| {
+        formatter
+            .map_meta(|variable| match variable {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(config.style),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "number" => Some(module_number.clone()),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `jobs`:\n{}", error);
+            return None;
+        }
+    });
 
     Some(module)
 }
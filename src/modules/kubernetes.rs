@@ -3,13 +3,12 @@ use yaml_rust::YamlLoader;
 use std::env;
 use std::path;
 
-use super::{Context, Module, RootModuleConfig};
+use super::{Context, Module, Redaction, RootModuleConfig};
 
 use crate::configs::kubernetes::KubernetesConfig;
+use crate::formatter::StringFormatter;
 use crate::utils;
 
-const KUBERNETES_PREFIX: &str = "on ";
-
 fn get_kube_context(contents: &str) -> Option<(String, String)> {
     let yaml_docs = YamlLoader::load_from_str(&contents).ok()?;
     if yaml_docs.is_empty() {
@@ -42,6 +41,10 @@ fn parse_kubectl_file(filename: &path::PathBuf) -> Option<(String, String)> {
     get_kube_context(&contents)
 }
 
+/// The context and namespace are masked when redaction is enabled
+pub const REDACTIONS: &[(&str, Redaction)] =
+    &[("context", Redaction::Mask), ("namespace", Redaction::Mask)];
+
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let kube_cfg = match env::var("KUBECONFIG") {
         Ok(paths) => env::split_paths(&paths)
@@ -63,23 +66,37 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 return None;
             };
 
-            module.set_style(config.style);
-            module.get_prefix().set_value(KUBERNETES_PREFIX);
-
-            module.create_segment("symbol", &config.symbol);
-
             let displayed_context = match config.context_aliases.get(&kube_ctx) {
                 None => &kube_ctx,
                 Some(&alias) => alias,
             };
 
-            module.create_segment("context", &config.context.with_value(&displayed_context));
-            if kube_ns != "" {
-                module.create_segment(
-                    "namespace",
-                    &config.namespace.with_value(&format!(" ({})", kube_ns)),
-                );
-            }
+            let parsed = StringFormatter::new(config.format).map(|formatter| {
+                formatter
+                    .map_meta(|variable| match variable {
+                        "symbol" => Some(config.symbol),
+                        _ => None,
+                    })
+                    .map_style(|variable| match variable {
+                        "style" => Some(config.style),
+                        _ => None,
+                    })
+                    .map(|variable| match variable {
+                        "context" => Some(displayed_context.to_string()),
+                        "namespace" => Some(kube_ns.clone()),
+                        _ => None,
+                    })
+                    .parse(None, Some(context))
+            });
+
+            module.set_segments(match parsed {
+                Ok(segments) => segments,
+                Err(error) => {
+                    log::warn!("Error in module `kubernetes`:\n{}", error);
+                    return None;
+                }
+            });
+
             Some(module)
         }
         None => None,
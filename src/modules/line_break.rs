@@ -1,5 +1,5 @@
 use super::{Context, Module};
-use crate::config::SegmentConfig;
+use crate::segment::Segment;
 
 /// Creates a module for the line break
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
@@ -7,10 +7,9 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
 
     let mut module = context.new_module("line_break");
 
-    module.get_prefix().set_value("");
-    module.get_suffix().set_value("");
-
-    module.create_segment("character", &SegmentConfig::new(LINE_ENDING));
+    let mut segment = Segment::new("line_break");
+    segment.set_value(LINE_ENDING);
+    module.set_segments(vec![segment]);
 
     Some(module)
 }
@@ -4,6 +4,7 @@ use sysinfo::{RefreshKind, SystemExt};
 use super::{Context, Module, RootModuleConfig, Shell};
 
 use crate::configs::memory_usage::MemoryConfig;
+use crate::formatter::StringFormatter;
 
 fn format_kib(n_kib: u64) -> String {
     let byte = Byte::from_unit(n_kib as f64, ByteUnit::KiB).unwrap_or_else(|_| Byte::from_bytes(0));
@@ -28,9 +29,6 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
-    module.set_style(config.style);
-    module.create_segment("symbol", &config.symbol);
-
     let system = sysinfo::System::new_with_specifics(RefreshKind::new().with_memory());
 
     let used_memory_kib = system.get_used_memory();
@@ -44,38 +42,57 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
-    let show_percentage = config.show_percentage;
-
-    let ram = if show_percentage {
-        format!("{:.0}{}", percent_mem_used, percent_sign)
-    } else {
-        format!(
-            "{}/{}",
-            format_kib(used_memory_kib),
-            format_kib(total_memory_kib)
-        )
-    };
-    module.create_segment("ram", &config.ram.with_value(&ram));
+    let ram = format!(
+        "{}/{}",
+        format_kib(used_memory_kib),
+        format_kib(total_memory_kib)
+    );
+    let ram_pct = format!("{:.0}{}", percent_mem_used, percent_sign);
 
-    // swap only shown if enabled and there is swap on the system
+    // swap is only available if there is swap on the system
     let total_swap_kib = system.get_total_swap();
-    if config.show_swap && total_swap_kib > 0 {
-        let used_swap_kib = system.get_used_swap();
+    let used_swap_kib = system.get_used_swap();
+    let (swap, swap_pct) = if total_swap_kib > 0 {
         let percent_swap_used = (used_swap_kib as f64 / total_swap_kib as f64) * 100.;
-
-        let swap = if show_percentage {
-            format!("{:.0}{}", percent_swap_used, percent_sign)
-        } else {
-            format!(
+        (
+            Some(format!(
                 "{}/{}",
                 format_kib(used_swap_kib),
                 format_kib(total_swap_kib)
-            )
-        };
+            )),
+            Some(format!("{:.0}{}", percent_swap_used, percent_sign)),
+        )
+    } else {
+        (None, None)
+    };
 
-        module.create_segment("separator", &config.separator);
-        module.create_segment("swap", &config.swap.with_value(&swap));
-    }
+    let parsed = StringFormatter::new(config.format).map(|formatter| {
+        formatter
+            .map_meta(|variable| match variable {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(config.style),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "ram" => Some(ram.clone()),
+                "ram_pct" => Some(ram_pct.clone()),
+                "swap" => swap.clone(),
+                "swap_pct" => swap_pct.clone(),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `memory_usage`:\n{}", error);
+            return None;
+        }
+    });
 
     Some(module)
 }
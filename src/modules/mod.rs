@@ -12,6 +12,7 @@ mod elixir;
 mod elm;
 mod env_var;
 mod erlang;
+pub(crate) mod fill;
 mod git_branch;
 mod git_commit;
 mod git_state;
@@ -46,9 +47,9 @@ mod zig;
 #[cfg(feature = "battery")]
 mod battery;
 
-use crate::config::{RootModuleConfig, SegmentConfig};
+use crate::config::RootModuleConfig;
 use crate::context::{Context, Shell};
-use crate::module::Module;
+use crate::module::{Module, Redaction};
 
 pub fn handle<'a>(module: &str, context: &'a Context) -> Option<Module<'a>> {
     match module {
@@ -67,6 +68,7 @@ pub fn handle<'a>(module: &str, context: &'a Context) -> Option<Module<'a>> {
         "elm" => elm::module(context),
         "erlang" => erlang::module(context),
         "env_var" => env_var::module(context),
+        "fill" => fill::module(context),
         "git_branch" => git_branch::module(context),
         "git_commit" => git_commit::module(context),
         "git_state" => git_state::module(context),
@@ -119,6 +121,7 @@ pub fn description(module: &str) -> &'static str {
         "dotnet" => "The relevant version of the .NET Core SDK for the current directory",
         "env_var" => "Displays the current value of a selected environment variable",
         "erlang" => "Current OTP version",
+        "fill" => "Fills the remaining space of the line",
         "git_branch" => "The active branch of the repo in your current directory",
         "git_commit" => "The active commit of the repo in your current directory",
         "git_state" => "The current git operation, and it's progress",
@@ -150,3 +153,42 @@ pub fn description(module: &str) -> &'static str {
         _ => "<no description>",
     }
 }
+
+/// The variables of a module which are hidden when redaction is enabled
+pub fn redactions(module: &str) -> &'static [(&'static str, Redaction)] {
+    match module {
+        "aws" => aws::REDACTIONS,
+        "directory" => directory::REDACTIONS,
+        "docker_context" => docker_context::REDACTIONS,
+        "env_var" => env_var::REDACTIONS,
+        "hostname" => hostname::REDACTIONS,
+        "kubernetes" => kubernetes::REDACTIONS,
+        "username" => username::REDACTIONS,
+        _ => &[],
+    }
+}
+
+/// The priority of a module which doesn't set one, including custom modules
+pub const DEFAULT_PRIORITY: i64 = 50;
+
+/// The priority of a module which doesn't set one
+///
+/// When the prompt is wider than the terminal, the modules with the lowest priority are
+/// shortened or dropped first.
+pub fn default_priority(module: &str) -> i64 {
+    match module {
+        "character" => 100,
+        "directory" => 90,
+        "git_branch" | "git_state" | "hg_branch" => 80,
+        "git_status" | "jobs" => 70,
+        "battery" | "package" => 30,
+        "memory_usage" | "time" => 20,
+        _ => DEFAULT_PRIORITY,
+    }
+}
+
+/// Whether a module has a short form, used before dropping it from a prompt wider than
+/// the terminal
+pub fn can_shorten(module: &str) -> bool {
+    matches!(module, "directory" | "git_branch")
+}
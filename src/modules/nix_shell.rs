@@ -1,8 +1,9 @@
 use std::env;
 
-use super::{Context, Module, RootModuleConfig, SegmentConfig};
+use super::{Context, Module, RootModuleConfig};
 
 use crate::configs::nix_shell::NixShellConfig;
+use crate::formatter::StringFormatter;
 
 // IN_NIX_SHELL should be "pure" or "impure" but lorri uses "1" for "impure"
 // https://github.com/target/lorri/issues/140
@@ -13,43 +14,51 @@ use crate::configs::nix_shell::NixShellConfig;
 /// determine if it's inside a nix-shell and the name of it.
 ///
 /// The following options are availables:
-///     - use_name   (bool)   // print the name of the nix-shell
 ///     - impure_msg (string) // change the impure msg
 ///     - pure_msg (string)   // change the pure msg
 ///
-/// Will display the following:
-///     - name (pure)    // use_name == true in a pure nix-shell
-///     - name (impure)  // use_name == true in an impure nix-shell
-///     - pure           // use_name == false in a pure nix-shell
-///     - impure         // use_name == false in an impure nix-shell
+/// The following variables are available in `format`:
+///     - $state  // `pure_msg` or `impure_msg`, depending on the nix-shell
+///     - $name   // the name of the nix-shell
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("nix_shell");
     let config: NixShellConfig = NixShellConfig::try_load(module.config);
 
-    module.set_style(config.style);
-    module.create_segment("symbol", &config.symbol);
-
     let shell_type = env::var("IN_NIX_SHELL").ok()?;
-    let shell_type_segment: SegmentConfig = match shell_type.as_ref() {
+    let shell_type_format = match shell_type.as_ref() {
         "1" | "impure" => config.impure_msg,
         "pure" => config.pure_msg,
         _ => {
             return None;
         }
     };
+    let shell_name = env::var("name").ok();
+
+    let parsed = StringFormatter::new(config.format).map(|formatter| {
+        formatter
+            .map_meta(|variable| match variable {
+                "symbol" => Some(config.symbol),
+                "state" => Some(shell_type_format),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(config.style),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "name" => shell_name.clone(),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
 
-    if config.use_name {
-        if let Ok(name) = env::var("name") {
-            module.create_segment(
-                "nix_shell",
-                &shell_type_segment.with_value(&format!("{} ({})", name, shell_type_segment.value)),
-            );
-        } else {
-            module.create_segment("nix_shell", &shell_type_segment);
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `nix_shell`:\n{}", error);
+            return None;
         }
-    } else {
-        module.create_segment("nix_shell", &shell_type_segment);
-    }
+    });
 
     Some(module)
 }
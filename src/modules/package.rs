@@ -6,8 +6,9 @@ use crate::utils;
 use regex::Regex;
 use serde_json as json;
 
-use super::{RootModuleConfig, SegmentConfig};
+use super::RootModuleConfig;
 use crate::configs::package::PackageConfig;
+use crate::formatter::StringFormatter;
 
 /// Creates a module with the current package version
 ///
@@ -16,18 +17,34 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("package");
     let config: PackageConfig = PackageConfig::try_load(module.config);
 
-    match get_package_version(&context.current_dir, &config) {
-        Some(package_version) => {
-            module.set_style(config.style);
-            module.get_prefix().set_value("is ");
-
-            module.create_segment("symbol", &config.symbol);
-            module.create_segment("version", &SegmentConfig::new(&package_version));
-
-            Some(module)
+    let package_version = get_package_version(&context.current_dir, &config)?;
+
+    let parsed = StringFormatter::new(config.format).map(|formatter| {
+        formatter
+            .map_meta(|variable| match variable {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(config.style),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "version" => Some(package_version.clone()),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `package`:\n{}", error);
+            return None;
         }
-        None => None,
-    }
+    });
+
+    Some(module)
 }
 
 fn extract_cargo_version(file_contents: &str) -> Option<String> {
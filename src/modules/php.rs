@@ -1,6 +1,7 @@
-use super::{Context, Module, RootModuleConfig, SegmentConfig};
+use super::{Context, Module, RootModuleConfig};
 
 use crate::configs::php::PhpConfig;
+use crate::formatter::StringFormatter;
 use crate::utils;
 
 /// Creates a module with the current PHP version
@@ -32,11 +33,32 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
             let mut module = context.new_module("php");
             let config: PhpConfig = PhpConfig::try_load(module.config);
 
-            module.set_style(config.style);
-
             let formatted_version = format_php_version(&php_version)?;
-            module.create_segment("symbol", &config.symbol);
-            module.create_segment("version", &SegmentConfig::new(&formatted_version));
+
+            let parsed = StringFormatter::new(config.format).map(|formatter| {
+                formatter
+                    .map_meta(|variable| match variable {
+                        "symbol" => Some(config.symbol),
+                        _ => None,
+                    })
+                    .map_style(|variable| match variable {
+                        "style" => Some(config.style),
+                        _ => None,
+                    })
+                    .map(|variable| match variable {
+                        "version" => Some(formatted_version.clone()),
+                        _ => None,
+                    })
+                    .parse(None, Some(context))
+            });
+
+            module.set_segments(match parsed {
+                Ok(segments) => segments,
+                Err(error) => {
+                    log::warn!("Error in module `php`:\n{}", error);
+                    return None;
+                }
+            });
 
             Some(module)
         }
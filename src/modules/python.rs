@@ -1,8 +1,9 @@
 use std::env;
 use std::path::Path;
 
-use super::{Context, Module, RootModuleConfig, SegmentConfig};
+use super::{Context, Module, RootModuleConfig};
 use crate::configs::python::PythonConfig;
+use crate::formatter::StringFormatter;
 use crate::utils;
 
 /// Creates a module with the current Python version
@@ -41,25 +42,41 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
-    module.set_style(config.style);
-    module.create_segment("symbol", &config.symbol);
-
-    if config.pyenv_version_name {
+    let (pyenv_prefix, python_version) = if config.pyenv_version_name {
         let python_version = utils::exec_cmd("pyenv", &["version-name"])?.stdout;
-        module.create_segment("pyenv_prefix", &config.pyenv_prefix);
-        module.create_segment("version", &SegmentConfig::new(&python_version.trim()));
+        (config.pyenv_prefix, python_version.trim().to_string())
     } else {
         let python_version = get_python_version(&config.python_binary)?;
-        let formatted_version = format_python_version(&python_version);
-        module.create_segment("version", &SegmentConfig::new(&formatted_version));
-    };
-
-    if let Some(virtual_env) = get_python_virtual_env() {
-        module.create_segment(
-            "virtualenv",
-            &SegmentConfig::new(&format!(" ({})", virtual_env)),
-        );
+        ("", format_python_version(&python_version))
     };
+    let virtual_env = get_python_virtual_env();
+
+    let parsed = StringFormatter::new(config.format).map(|formatter| {
+        formatter
+            .map_meta(|variable| match variable {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(config.style),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "pyenv_prefix" => Some(pyenv_prefix.to_string()),
+                "version" => Some(python_version.clone()),
+                "virtualenv" => virtual_env.clone(),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `python`:\n{}", error);
+            return None;
+        }
+    });
 
     Some(module)
 }
@@ -1,9 +1,10 @@
 use std::env;
 
-use super::{Context, Module, SegmentConfig};
+use super::{Context, Module};
 
 use crate::config::RootModuleConfig;
 use crate::configs::singularity::SingularityConfig;
+use crate::formatter::StringFormatter;
 
 /// Creates a module with the current Singularity image
 ///
@@ -17,12 +18,30 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("singularity");
     let config = SingularityConfig::try_load(module.config);
 
-    module.get_prefix().set_value(config.label);
-    module.set_style(config.style);
-    module.create_segment("symbol", &config.symbol);
+    let parsed = StringFormatter::new(config.format).map(|formatter| {
+        formatter
+            .map_meta(|variable| match variable {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(config.style),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "env" => Some(singularity_env.clone()),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
 
-    let env_var_stacked = format!("{}{}{}", config.prefix, singularity_env, config.suffix);
-    module.create_segment("singularity", &SegmentConfig::new(&env_var_stacked));
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `singularity`:\n{}", error);
+            return None;
+        }
+    });
 
     Some(module)
 }
@@ -1,10 +1,11 @@
 use super::{Context, Module, RootModuleConfig};
 
 use crate::configs::terraform::TerraformConfig;
+use crate::formatter::StringFormatter;
 use crate::utils;
 use std::env;
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Creates a module with the current Terraform version and workspace
 ///
@@ -25,26 +26,41 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("terraform");
     let config: TerraformConfig = TerraformConfig::try_load(module.config);
 
-    module.set_style(config.style);
-    module.create_segment("symbol", &config.symbol);
-
-    if config.show_version {
-        let terraform_version =
-            format_terraform_version(&utils::exec_cmd("terraform", &["version"])?.stdout.as_str())?;
-        module.create_segment("version", &config.version.with_value(&terraform_version));
-    }
-
-    let terraform_workspace = &get_terraform_workspace(&context.current_dir)?;
-    module.create_segment(
-        "workspace",
-        &config.workspace.with_value(&terraform_workspace),
-    );
+    let terraform_workspace = get_terraform_workspace(&context.current_dir)?;
+
+    let parsed = StringFormatter::new(config.format).map(|formatter| {
+        formatter
+            .map_meta(|variable| match variable {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(config.style),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "workspace" => Some(terraform_workspace.clone()),
+                "version" => format_terraform_version(
+                    utils::exec_cmd("terraform", &["version"])?.stdout.as_str(),
+                ),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `terraform`:\n{}", error);
+            return None;
+        }
+    });
 
     Some(module)
 }
 
 // Determines the currently selected workspace (see https://github.com/hashicorp/terraform/blob/master/command/meta.go for the original implementation)
-fn get_terraform_workspace(cwd: &PathBuf) -> Option<String> {
+fn get_terraform_workspace(cwd: &Path) -> Option<String> {
     // Workspace can be explicitly overwritten by an env var
     let workspace_override = env::var("TF_WORKSPACE");
     if workspace_override.is_ok() {
@@ -73,8 +89,7 @@ fn format_terraform_version(version: &str) -> Option<String> {
             .next()?
             .trim_start_matches("Terraform ")
             .trim()
-            .to_owned()
-            + " ",
+            .to_owned(),
     )
 }
 
@@ -87,7 +102,7 @@ mod tests {
         let input = "Terraform v0.12.14";
         assert_eq!(
             format_terraform_version(input),
-            Some("v0.12.14 ".to_string())
+            Some("v0.12.14".to_string())
         );
     }
 
@@ -96,7 +111,7 @@ mod tests {
         let input = "Terraform v0.12.14-rc1";
         assert_eq!(
             format_terraform_version(input),
-            Some("v0.12.14-rc1 ".to_string())
+            Some("v0.12.14-rc1".to_string())
         );
     }
 
@@ -105,7 +120,7 @@ mod tests {
         let input = "Terraform v0.12.14-dev (cca89f74)";
         assert_eq!(
             format_terraform_version(input),
-            Some("v0.12.14-dev (cca89f74) ".to_string())
+            Some("v0.12.14-dev (cca89f74)".to_string())
         );
     }
 
@@ -119,7 +134,7 @@ is 0.12.14. You can update by downloading from www.terraform.io/downloads.html
 ";
         assert_eq!(
             format_terraform_version(input),
-            Some("v0.12.13 ".to_string())
+            Some("v0.12.13".to_string())
         );
     }
 }
@@ -1,10 +1,14 @@
 use std::env;
 
-use super::{Context, Module, RootModuleConfig, SegmentConfig};
+use super::{Context, Module, Redaction, RootModuleConfig};
 
 use crate::configs::username::UsernameConfig;
+use crate::formatter::StringFormatter;
 use crate::utils;
 
+/// The username is masked when redaction is enabled
+pub const REDACTIONS: &[(&str, Redaction)] = &[("user", Redaction::Mask)];
+
 /// Creates a module with the current user's username
 ///
 /// Will display the username if any of the following criteria are met:
@@ -27,9 +31,28 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
             Some(0) => config.style_root,
             _ => config.style_user,
         };
+        let user = user?;
+
+        let parsed = StringFormatter::new(config.format).map(|formatter| {
+            formatter
+                .map_style(|variable| match variable {
+                    "style" => Some(module_style),
+                    _ => None,
+                })
+                .map(|variable| match variable {
+                    "user" => Some(user.clone()),
+                    _ => None,
+                })
+                .parse(None, Some(context))
+        });
 
-        module.set_style(module_style);
-        module.create_segment("username", &SegmentConfig::new(&user?));
+        module.set_segments(match parsed {
+            Ok(segments) => segments,
+            Err(error) => {
+                log::warn!("Error in module `username`:\n{}", error);
+                return None;
+            }
+        });
 
         Some(module)
     } else {
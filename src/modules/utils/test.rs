@@ -1,4 +1,4 @@
-use crate::config::StarshipConfig;
+use crate::config::{ColorDepth, StarshipConfig};
 use crate::context::{Context, Shell};
 use std::path::Path;
 
@@ -11,6 +11,7 @@ pub fn render_module(
     let mut context = Context::new_with_dir(clap::ArgMatches::default(), path);
     context.config = StarshipConfig { config };
     context.shell = Shell::Unknown;
+    context.color_depth = ColorDepth::TrueColor;
 
     crate::print::get_module(module_name, context)
 }
@@ -0,0 +1,60 @@
+/// A configuration bundled with starship, which can be used as a base layer with the
+/// root `preset` option or written to the configuration file with `starship preset`
+pub struct Preset {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub config: &'static str,
+}
+
+pub const PRESETS: &[Preset] = &[
+    Preset {
+        name: "nerd-font-symbols",
+        description: "Nerd Font glyphs for the symbols of each module",
+        config: include_str!("nerd-font-symbols.toml"),
+    },
+    Preset {
+        name: "plain-text-symbols",
+        description: "Plain text for the symbols of each module, for terminals without emoji",
+        config: include_str!("plain-text-symbols.toml"),
+    },
+    Preset {
+        name: "pure-preset",
+        description: "Emulates the look and feel of the Pure prompt",
+        config: include_str!("pure-preset.toml"),
+    },
+    Preset {
+        name: "powerline",
+        description: "Colored blocks joined by powerline separators",
+        config: include_str!("powerline.toml"),
+    },
+];
+
+/// Find a bundled preset by its name
+pub fn get_preset(name: &str) -> Option<&'static Preset> {
+    PRESETS.iter().find(|preset| preset.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs;
+
+    #[test]
+    fn test_presets_are_valid() {
+        for preset in PRESETS {
+            let config: toml::Value = toml::from_str(preset.config)
+                .unwrap_or_else(|error| panic!("{} isn't valid TOML: {}", preset.name, error));
+            let errors = configs::validate(&config, &config);
+            assert!(errors.is_empty(), "{}: {:?}", preset.name, errors);
+        }
+    }
+
+    #[test]
+    fn test_get_preset() {
+        assert_eq!(
+            get_preset("powerline").map(|preset| preset.name),
+            Some("powerline")
+        );
+        assert!(get_preset("unknown").is_none());
+    }
+}
@@ -1,14 +1,19 @@
-use ansi_term::ANSIStrings;
+use ansi_term::{ANSIStrings, Color, Style};
 use clap::ArgMatches;
 use rayon::prelude::*;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
 use std::fmt::{self, Debug, Write as FmtWrite};
 use std::io::{self, Write};
 use unicode_width::UnicodeWidthChar;
 
+use crate::configs::{StarshipRootConfig, PROMPT_ORDER};
 use crate::context::{Context, Shell};
+use crate::formatter::StringFormatter;
 use crate::module::Module;
 use crate::module::ALL_MODULES;
 use crate::modules;
+use crate::segment::Segment;
 
 pub fn prompt(args: ArgMatches) {
     let context = Context::new(args);
@@ -17,37 +22,132 @@ pub fn prompt(args: ArgMatches) {
     write!(handle, "{}", get_prompt(context)).unwrap();
 }
 
-pub fn get_prompt(context: Context) -> String {
+pub fn get_prompt(mut context: Context) -> String {
     let config = context.config.get_root_config();
     let mut buf = String::new();
 
-    // Write a new line before the prompt
-    if config.add_newline {
-        writeln!(buf).unwrap();
-    }
+    let (format, format_name) = match (context.transient, context.right) {
+        _ if context.continuation => (config.continuation_prompt, "continuation_prompt"),
+        (false, false) => (config.format, "format"),
+        (false, true) => (config.right_format, "right_format"),
+        (true, false) => (config.transient_format, "transient_format"),
+        (true, true) => (config.transient_right_format, "transient_right_format"),
+    };
+
+    if !context.right && !context.continuation {
+        // Write a new line before the prompt
+        if config.add_newline {
+            // tcsh can't be given a newline in its prompt, and reads `\n` instead
+            if let Shell::Tcsh = context.shell {
+                buf.push_str("\\n");
+            } else {
+                writeln!(buf).unwrap();
+            }
+        }
 
-    // A workaround for a fish bug (see #739,#279). Applying it to all shells
-    // breaks things (see #808,#824,#834). Should only be printed in fish.
-    if let Shell::Fish = context.shell {
-        buf.push_str("\x1b[J"); // An ASCII control code to clear screen
+        // A workaround for a fish bug (see #739,#279). Applying it to all shells
+        // breaks things (see #808,#824,#834). Should only be printed in fish.
+        if let Shell::Fish = context.shell {
+            buf.push_str("\x1b[J"); // An ASCII control code to clear screen
+        }
     }
 
-    let modules = compute_modules(&context);
+    let formatter = match StringFormatter::new(format) {
+        Ok(formatter) => formatter,
+        Err(error) => {
+            log::error!("Error parsing `{}`:\n{}", format_name, error);
+            if !context.right {
+                buf.push('>');
+            }
+            return buf;
+        }
+    };
+    let modules = listed_modules(&config);
+    let powerline = config.powerline;
+    let mut variable_modules = formatter
+        .get_variables()
+        .into_par_iter()
+        .map(|variable| {
+            // Make $all display all modules which aren't explicitly listed in either format
+            let variable_modules = if variable == "all" {
+                all_modules_uniq(&modules)
+                    .par_iter()
+                    .flat_map(|module| prompt_modules(module, &context, &modules))
+                    .collect()
+            } else {
+                prompt_modules(&variable, &context, &modules)
+            };
+            (variable, variable_modules)
+        })
+        .collect::<Vec<(String, Vec<PromptModule>)>>();
+
+    // Only a width given by the shell is reliable enough to drop modules
+    let width = context
+        .properties
+        .get("terminal_width")
+        .and_then(|width| width.trim().parse::<usize>().ok());
+    let fit_to_width = matches!(width, Some(width) if width > 0);
+    let mut segments = loop {
+        let mut segments =
+            render_modules(formatter.clone(), &variable_modules, &context, powerline);
+        if powerline {
+            if context.right {
+                fill_powerline_separators(&mut segments, config.powerline_right_separator, true);
+            } else {
+                fill_powerline_separators(&mut segments, config.powerline_separator, false);
+            }
+        }
+        if !fit_to_width {
+            break segments;
+        }
 
-    let mut print_without_prefix = true;
-    let printable = modules.iter();
+        // Shorten or drop the module with the lowest priority on a line wider than the
+        // terminal, the last one of the prompt among modules with the same priority
+        let overflowing = overflowing_modules(&segments, context.width);
+        let module = variable_modules
+            .iter_mut()
+            .flat_map(|(_, modules)| modules.iter_mut())
+            .enumerate()
+            .filter(|(index, module)| overflowing.contains(index) && module.can_drop())
+            .min_by_key(|(index, module)| (module.priority, Reverse(*index)));
+        let module = match module {
+            Some((_, module)) => module,
+            None => break segments,
+        };
 
-    for module in printable {
-        // Skip printing the prefix of a module after the line_break
-        if print_without_prefix {
-            let module_without_prefix = module.to_string_without_prefix(context.shell);
-            write!(buf, "{}", module_without_prefix).unwrap()
+        if modules::can_shorten(&module.name) && !context.shortened.contains(&module.name) {
+            context.shortened.insert(module.name.clone());
+            match prompt_modules(&module.name, &context, &modules).pop() {
+                Some(shortened) => module.segments = shortened.segments,
+                None => module.dropped = true,
+            }
         } else {
-            let module = module.ansi_strings_for_shell(context.shell);
-            write!(buf, "{}", ANSIStrings(&module)).unwrap();
+            module.dropped = true;
         }
-
-        print_without_prefix = module.get_name() == "line_break"
+    };
+    segments.retain(|segment| !segment._name.starts_with(MODULE_MARKER));
+    fill_lines(&mut segments, context.width);
+    let prompt_width = segments
+        .iter()
+        .map(|segment| display_width(&segment.value))
+        .sum::<usize>();
+
+    let mut root_module = Module::new("Starship Root", "The root module", None);
+    root_module.set_segments(segments);
+    root_module.set_color_depth(context.color_depth);
+
+    let module_strings = root_module.ansi_strings_for_shell(context.shell);
+    let prompt = ANSIStrings(&module_strings).to_string();
+    match context.shell {
+        // PowerShell has no right prompt, so it is drawn on the line of the prompt: the
+        // cursor is saved, moved to the column the right prompt starts at, and restored
+        Shell::PowerShell if context.right => {
+            if prompt_width > 0 && prompt_width <= context.width {
+                let column = context.width - prompt_width + 1;
+                write!(buf, "\x1b[s\x1b[{}G{}\x1b[u", column, prompt).unwrap();
+            }
+        }
+        _ => buf.push_str(&prompt),
     }
 
     buf
@@ -60,7 +160,13 @@ pub fn module(module_name: &str, args: ArgMatches) {
 }
 
 pub fn get_module(module_name: &str, context: Context) -> Option<String> {
-    modules::handle(module_name, &context).map(|m| m.to_string())
+    modules::handle(module_name, &context).map(|mut m| {
+        if context.redact {
+            m.redact(modules::redactions(module_name));
+        }
+        m.set_color_depth(context.color_depth);
+        m.to_string()
+    })
 }
 
 pub fn explain(args: ArgMatches) {
@@ -72,16 +178,17 @@ pub fn explain(args: ArgMatches) {
         desc: String,
     }
 
-    let dont_print = vec!["line_break", "character"];
+    let dont_print = ["line_break", "character", "fill"];
 
     let modules = compute_modules(&context)
         .into_iter()
         .filter(|module| !dont_print.contains(&module.get_name().as_str()))
-        .map(|module| {
-            let ansi_strings = module.ansi_strings();
+        .filter(|module| !module.is_empty())
+        .map(|mut module| {
+            module.set_color_depth(context.color_depth);
             let value = module.get_segments().join("");
             ModuleInfo {
-                value: ansi_term::ANSIStrings(&ansi_strings[1..ansi_strings.len() - 1]).to_string(),
+                value: ansi_term::ANSIStrings(&module.ansi_strings()).to_string(),
                 value_len: value.chars().count() + count_wide_chars(&value),
                 desc: module.get_description().to_owned(),
             }
@@ -132,11 +239,311 @@ pub fn explain(args: ArgMatches) {
 }
 
 fn compute_modules<'a>(context: &'a Context) -> Vec<Module<'a>> {
-    enum Mod<'a> {
-        Builtin(&'a str),
-        Custom(&'a str),
+    let config = context.config.get_root_config();
+    let modules = listed_modules(&config);
+
+    // Expand $all in place, so that modules keep the order they are printed in
+    let prompt_order = [config.format, config.right_format]
+        .iter()
+        .filter_map(|format| StringFormatter::new(format).ok())
+        .flat_map(|formatter| formatter.get_variables())
+        .flat_map(|module| {
+            if module == "all" {
+                all_modules_uniq(&modules)
+            } else {
+                vec![module]
+            }
+        })
+        .collect::<Vec<String>>();
+
+    prompt_order
+        .par_iter()
+        .flat_map(|module| handle_module(module, context, &modules))
+        .collect::<Vec<Module<'a>>>()
+}
+
+/// Returns the names of the variables used in both `format` and `right_format`
+fn listed_modules(config: &StarshipRootConfig) -> Vec<String> {
+    [config.format, config.right_format]
+        .iter()
+        .filter_map(|format| StringFormatter::new(format).ok())
+        .flat_map(|formatter| formatter.get_variables())
+        .collect()
+}
+
+/// Returns the modules of the default prompt order which aren't explicitly listed
+/// in the root formats
+fn all_modules_uniq(module_list: &[String]) -> Vec<String> {
+    PROMPT_ORDER
+        .iter()
+        .filter(|module| !module_list.iter().any(|listed| listed == *module))
+        .map(|module| module.to_string())
+        .collect()
+}
+
+/// A module of the prompt, which can be shortened or dropped when the prompt is wider than
+/// the terminal
+struct PromptModule {
+    name: String,
+    priority: i64,
+    segments: Vec<Segment>,
+    dropped: bool,
+}
+
+impl PromptModule {
+    /// Whether dropping the module could make its line narrower
+    ///
+    /// A module ending a line, such as `line_break`, is kept so that lines aren't joined.
+    fn can_drop(&self) -> bool {
+        !self.dropped
+            && self
+                .segments
+                .iter()
+                .any(|segment| display_width(&segment.value) > 0)
+            && self
+                .segments
+                .iter()
+                .all(|segment| !segment.value.contains('\n'))
+    }
+}
+
+/// Computes the module(s) referenced by a root format variable, with their priority
+fn prompt_modules(module: &str, context: &Context, module_list: &[String]) -> Vec<PromptModule> {
+    let is_custom = module.starts_with("custom");
+    handle_module(module, context, module_list)
+        .into_iter()
+        .map(|module| {
+            let name = module.get_name().to_string();
+            let priority = module
+                .config
+                .and_then(|config| config.get("priority"))
+                .and_then(toml::Value::as_integer)
+                .unwrap_or_else(|| match is_custom {
+                    true => modules::DEFAULT_PRIORITY,
+                    false => modules::default_priority(&name),
+                });
+            PromptModule {
+                name,
+                priority,
+                segments: module.segments,
+                dropped: false,
+            }
+        })
+        .collect()
+}
+
+/// Prefix of the name of the empty segments marking where each module starts, followed by
+/// the index of the module
+const MODULE_MARKER: &str = "_module_";
+
+/// Maps the variables of the root format to the segments of the modules which haven't been
+/// dropped, and parses it
+///
+/// Each module is preceded by a marker, which tells the module a line of the prompt starts
+/// with. In powerline mode, a placeholder marks where the separator of each module goes:
+/// after it in the left prompt, and before it in the right prompt.
+fn render_modules(
+    formatter: StringFormatter,
+    variable_modules: &[(String, Vec<PromptModule>)],
+    context: &Context,
+    powerline: bool,
+) -> Vec<Segment> {
+    let mut index = 0;
+    let mut variables = HashMap::new();
+    for (variable, modules) in variable_modules {
+        let mut segments = Vec::new();
+        for module in modules {
+            let marker = Segment::new(&format!("{}{}", MODULE_MARKER, index));
+            index += 1;
+            if module.dropped {
+                continue;
+            }
+
+            let is_empty = module.segments.iter().all(Segment::is_empty);
+            let separator = powerline && !is_empty;
+            segments.push(marker);
+            if separator && context.right {
+                segments.push(Segment::new(POWERLINE_SEPARATOR));
+            }
+            segments.extend(module.segments.iter().cloned());
+            if separator && !context.right {
+                segments.push(Segment::new(POWERLINE_SEPARATOR));
+            }
+        }
+        variables.insert(variable.as_str(), segments);
+    }
+
+    formatter
+        .map_variables_to_segments(|variable| variables.get(variable).cloned())
+        .parse(None, Some(context))
+}
+
+/// Returns the indices of the modules found on a line wider than `width`
+///
+/// A module belongs to the line its marker is on, and the fill segments are left out of the
+/// width of a line since they only take the columns left.
+fn overflowing_modules(segments: &[Segment], width: usize) -> HashSet<usize> {
+    let mut overflowing = HashSet::new();
+    let mut line_modules = Vec::new();
+    let mut used = 0;
+    for segment in segments {
+        if let Some(index) = segment._name.strip_prefix(MODULE_MARKER) {
+            line_modules.extend(index.parse::<usize>());
+            continue;
+        }
+        if segment._name == modules::fill::FILL_SEGMENT {
+            continue;
+        }
+
+        let mut lines = segment.value.split('\n');
+        used += lines.next().map_or(0, display_width);
+        for line in lines {
+            if used > width {
+                overflowing.extend(line_modules.iter());
+            }
+            line_modules.clear();
+            used = display_width(line);
+        }
+    }
+    if used > width {
+        overflowing.extend(line_modules);
+    }
+    overflowing
+}
+
+/// Name of the placeholder segments replaced by powerline separators
+const POWERLINE_SEPARATOR: &str = "_powerline_separator";
+
+/// Replace the powerline placeholders with `separator`, colored to go from the background
+/// of the segment before it to the background of the segment after it
+///
+/// In the left prompt, the separator continues the background of the module before it, and
+/// is hidden when that module has no background. In the right prompt, the separator leads
+/// into the background of the module after it.
+fn fill_powerline_separators(segments: &mut [Segment], separator: &str, right: bool) {
+    fn background<'a>(mut segments: impl Iterator<Item = &'a Segment>) -> Option<Color> {
+        segments
+            .find(|segment| !segment.value.is_empty())
+            .and_then(|segment| segment.style)
+            .and_then(|style| style.background)
+    }
+
+    for index in 0..segments.len() {
+        if segments[index]._name != POWERLINE_SEPARATOR {
+            continue;
+        }
+
+        let before = background(segments[..index].iter().rev());
+        let after = background(segments[index + 1..].iter());
+        let (color, background) = if right {
+            (after, before)
+        } else {
+            (before, after)
+        };
+
+        let placeholder = &mut segments[index];
+        placeholder.style = None;
+        placeholder.value = String::new();
+        if let Some(color) = color {
+            placeholder.set_value(separator);
+            placeholder.set_style(Style {
+                foreground: Some(color),
+                background,
+                ..Style::new()
+            });
+        }
     }
+}
 
+/// Expand the segments of the fill module to the columns left on their line
+///
+/// The columns left on a line are shared between the fill segments of that line. A line
+/// wider than the terminal leaves its fill segments empty.
+fn fill_lines(segments: &mut [Segment], width: usize) {
+    fn expand(segments: &mut [Segment], fills: &mut Vec<usize>, columns: usize) {
+        let count = fills.len();
+        for (nth, index) in fills.drain(..).enumerate() {
+            // The first segments get the columns which can't be shared evenly
+            let columns = columns / count + usize::from(nth < columns % count);
+            let segment = &mut segments[index];
+            segment.value = repeat_to_width(&segment.value, columns);
+            if columns == 0 {
+                segment.style = None;
+            }
+        }
+    }
+
+    // The width of each line of each segment, ignoring the fill segments
+    let line_widths = segments
+        .iter()
+        .map(|segment| match segment._name.as_str() {
+            modules::fill::FILL_SEGMENT => vec![0],
+            _ => segment.value.split('\n').map(display_width).collect(),
+        })
+        .collect::<Vec<Vec<usize>>>();
+
+    let mut fills = Vec::new();
+    let mut used = 0;
+    for (index, widths) in line_widths.into_iter().enumerate() {
+        if segments[index]._name == modules::fill::FILL_SEGMENT {
+            fills.push(index);
+            continue;
+        }
+        used += widths[0];
+        // Each further line of the segment starts after a newline, which ends the current line
+        for width_of_line in &widths[1..] {
+            expand(segments, &mut fills, width.saturating_sub(used));
+            used = *width_of_line;
+        }
+    }
+    expand(segments, &mut fills, width.saturating_sub(used));
+}
+
+/// Repeat `symbol` to fill `width` columns, padding with spaces when its last character
+/// doesn't fit
+fn repeat_to_width(symbol: &str, width: usize) -> String {
+    if display_width(symbol) == 0 {
+        return " ".repeat(width);
+    }
+
+    let mut filled = String::new();
+    let mut filled_width = 0;
+    for c in symbol.chars().cycle() {
+        let char_width = c.width().unwrap_or(0);
+        if filled_width + char_width > width {
+            break;
+        }
+        filled.push(c);
+        filled_width += char_width;
+    }
+    filled.push_str(&" ".repeat(width - filled_width));
+    filled
+}
+
+/// The number of columns taken by `text` in the terminal, ignoring ANSI escape sequences
+fn display_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // Skip to the final byte of the sequence, a letter for the usual `ESC [ ... m`
+            chars.find(|c| c.is_ascii_alphabetic());
+            continue;
+        }
+        width += c.width().unwrap_or(0);
+    }
+    width
+}
+
+/// Computes the module(s) referenced by a root format variable
+///
+/// The sensitive variables of the modules are hidden when redaction is enabled.
+/// `custom` expands to all custom modules which aren't explicitly listed in the format.
+fn handle_module<'a>(
+    module: &str,
+    context: &'a Context,
+    module_list: &[String],
+) -> Vec<Module<'a>> {
     struct DebugCustomModules<'tmp>(&'tmp toml::value::Table);
 
     impl Debug for DebugCustomModules<'_> {
@@ -145,72 +552,70 @@ fn compute_modules<'a>(context: &'a Context) -> Vec<Module<'a>> {
         }
     }
 
-    let mut prompt_order: Vec<Mod> = Vec::new();
-
-    // Write out a custom prompt order
-    let config_prompt_order = context.config.get_root_config().prompt_order;
+    let mut modules: Vec<Option<Module>> = Vec::new();
 
-    for module in &config_prompt_order {
-        if ALL_MODULES.contains(module) {
-            // Write out a module if it isn't disabled
-            if !context.is_module_disabled_in_config(*module) {
-                prompt_order.push(Mod::Builtin(module));
-            }
-        } else if *module == "custom" {
-            // Write out all custom modules, except for those that are explicitly set
-            if let Some(custom_modules) = context.config.get_custom_modules() {
-                for (custom_module, config) in custom_modules {
-                    if should_add_implicit_custom_module(
-                        custom_module,
-                        config,
-                        &config_prompt_order,
-                    ) {
-                        prompt_order.push(Mod::Custom(custom_module));
-                    }
+    if ALL_MODULES.contains(&module) {
+        // Write out a module if it isn't disabled
+        if !context.is_module_disabled_in_config(module) {
+            modules.push(modules::handle(module, context));
+        }
+    } else if module == "custom" {
+        // Write out all custom modules, except for those that are explicitly set
+        if let Some(custom_modules) = context.config.get_custom_modules() {
+            for (custom_module, config) in custom_modules {
+                if should_add_implicit_custom_module(custom_module, config, module_list) {
+                    modules.push(modules::custom::module(custom_module, context));
                 }
             }
-        } else if module.starts_with("custom.") {
-            // Write out a custom module if it isn't disabled (and it exists...)
-            match context.is_custom_module_disabled_in_config(&module[7..]) {
-                Some(true) => (), // Module is disabled, we don't add it to the prompt
-                Some(false) => prompt_order.push(Mod::Custom(&module[7..])),
-                None => match context.config.get_custom_modules() {
-                    Some(modules) => log::debug!(
-                        "prompt_order contains custom module \"{}\", but no configuration was provided. Configuration for the following modules were provided: {:?}",
-                        module,
-                        DebugCustomModules(modules),
-                    ),
-                    None => log::debug!(
-                        "prompt_order contains custom module \"{}\", but no configuration was provided.",
-                        module,
-                    ),
-                },
-            }
-        } else {
-            log::debug!(
-                "Expected prompt_order to contain value from {:?}. Instead received {}",
-                ALL_MODULES,
-                module,
-            );
         }
+    } else if let Some(custom_module) = module.strip_prefix("custom.") {
+        // Write out a custom module if it isn't disabled (and it exists...)
+        match context.is_custom_module_disabled_in_config(custom_module) {
+            Some(true) => (), // Module is disabled, we don't add it to the prompt
+            Some(false) => modules.push(modules::custom::module(custom_module, context)),
+            None => match context.config.get_custom_modules() {
+                Some(modules) => log::debug!(
+                    "top level format contains custom module \"{}\", but no configuration was provided. Configuration for the following modules were provided: {:?}",
+                    module,
+                    DebugCustomModules(modules),
+                ),
+                None => log::debug!(
+                    "top level format contains custom module \"{}\", but no configuration was provided.",
+                    module,
+                ),
+            },
+        }
+    } else {
+        log::debug!(
+            "Expected top level format to contain value from {:?}. Instead received {}",
+            ALL_MODULES,
+            module,
+        );
     }
 
-    prompt_order
-        .par_iter()
-        .map(|module| match module {
-            Mod::Builtin(builtin) => modules::handle(builtin, context),
-            Mod::Custom(custom) => modules::custom::module(custom, context),
-        }) // Compute segments
-        .flatten() // Remove segments set to `None`
-        .collect::<Vec<Module<'a>>>()
+    let redactions = if module.starts_with("custom") {
+        modules::custom::REDACTIONS
+    } else {
+        modules::redactions(module)
+    };
+    modules
+        .into_iter()
+        .flatten()
+        .map(|mut module| {
+            if context.redact {
+                module.redact(redactions);
+            }
+            module
+        })
+        .collect()
 }
 
 fn should_add_implicit_custom_module(
     custom_module: &str,
     config: &toml::Value,
-    config_prompt_order: &[&str],
+    module_list: &[String],
 ) -> bool {
-    let is_explicitly_specified = config_prompt_order.iter().any(|x| {
+    let is_explicitly_specified = module_list.iter().any(|x| {
         x.len() == 7 + custom_module.len() && &x[..7] == "custom." && &x[7..] == custom_module
     });
 
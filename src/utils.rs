@@ -142,6 +142,10 @@ pub fn wrap_colorseq_for_shell(ansi: String, shell: Shell) -> String {
 /// Many shells cannot deal with raw unprintable characters and miscompute the cursor position,
 /// leading to strange visual bugs like duplicated/missing chars. This function wraps a specified
 /// sequence in shell-specific escapes to avoid these problems.
+///
+/// The text outside of the sequences is escaped for the shells which expand it in their
+/// prompt: `%`, `!` and backslashes in tcsh, which also reads newlines as `\n`, and `{` and
+/// `}` in xonsh.
 pub fn wrap_seq_for_shell(
     ansi: String,
     shell: Shell,
@@ -163,18 +167,28 @@ pub fn wrap_seq_for_shell(
                 escaped = true;
                 match shell {
                     Shell::Bash => format!("{}{}", BASH_BEG, escape_begin),
-                    Shell::Zsh => format!("{}{}", ZSH_BEG, escape_begin),
+                    Shell::Tcsh | Shell::Zsh => format!("{}{}", ZSH_BEG, escape_begin),
                     _ => x.to_string(),
                 }
             } else if x == escape_end && escaped {
                 escaped = false;
                 match shell {
                     Shell::Bash => format!("{}{}", escape_end, BASH_END),
-                    Shell::Zsh => format!("{}{}", escape_end, ZSH_END),
+                    Shell::Tcsh | Shell::Zsh => format!("{}{}", escape_end, ZSH_END),
                     _ => x.to_string(),
                 }
-            } else {
+            } else if escaped {
                 x.to_string()
+            } else {
+                match (shell, x) {
+                    (Shell::Tcsh, '%') => "%%".to_string(),
+                    (Shell::Tcsh, '!') => "\\!".to_string(),
+                    (Shell::Tcsh, '\\') => "\\\\".to_string(),
+                    (Shell::Tcsh, '\n') => "\\n".to_string(),
+                    (Shell::Xonsh, '{') => "{{".to_string(),
+                    (Shell::Xonsh, '}') => "}}".to_string(),
+                    _ => x.to_string(),
+                }
             }
         })
         .collect();
@@ -312,5 +326,22 @@ mod tests {
         assert_eq!(&bresult3, "\\[OH NO\\]");
         assert_eq!(&bresult4, "herpaderp");
         assert_eq!(&bresult5, "");
+
+        let tresult0 = wrap_seq_for_shell(test0.to_string(), Shell::Tcsh, '\x1b', 'm');
+        let tresult1 = wrap_seq_for_shell("50% done!\n\\".to_string(), Shell::Tcsh, '\x1b', 'm');
+        let tresult2 = wrap_seq_for_shell("\x1b[1;2%m!".to_string(), Shell::Tcsh, '\x1b', 'm');
+
+        assert_eq!(&tresult0, "%{\x1b2m%}hellomynamekeyes%{\x1b2m%}");
+        assert_eq!(&tresult1, "50%% done\\!\\n\\\\");
+        assert_eq!(&tresult2, "%{\x1b[1;2%m%}\\!");
+
+        let xresult0 = wrap_seq_for_shell(test0.to_string(), Shell::Xonsh, '\x1b', 'm');
+        let xresult1 = wrap_seq_for_shell("{path}".to_string(), Shell::Xonsh, '\x1b', 'm');
+
+        assert_eq!(&xresult0, test0);
+        assert_eq!(&xresult1, "{{path}}");
+
+        let eresult0 = wrap_seq_for_shell("{50%}!".to_string(), Shell::Elvish, '\x1b', 'm');
+        assert_eq!(&eresult0, "{50%}!");
     }
 }
@@ -17,11 +17,17 @@ fn impl_module_config(dinput: DeriveInput) -> proc_macro::TokenStream {
 
     let mut from_config = quote! {};
     let mut load_config = quote! {};
+    let mut validate = quote! {};
+    let mut to_config = quote! {};
+    let mut schema = quote! {};
 
     if let syn::Data::Struct(data) = dinput.data {
         if let syn::Fields::Named(fields_named) = data.fields {
             let mut load_tokens = quote! {};
             let mut from_tokens = quote! {};
+            let mut validate_tokens = quote! {};
+            let mut to_config_tokens = quote! {};
+            let mut schema_tokens = quote! {};
 
             for field in fields_named.named.iter() {
                 let ident = field.ident.as_ref().unwrap();
@@ -35,6 +41,27 @@ fn impl_module_config(dinput: DeriveInput) -> proc_macro::TokenStream {
                 let new_from_tokens = quote! {
                     #ident: config.get(stringify!(#ident)).and_then(<#ty>::from_config)?,
                 };
+                // Fields named `style` or `*_style` hold style strings
+                let ident_str = ident.to_string();
+                let validate_style = if ident_str == "style" || ident_str.ends_with("_style") {
+                    quote! { crate::config::validate_style(value, &field_path, errors); }
+                } else {
+                    quote! {}
+                };
+                let new_to_config_tokens = quote! {
+                    if let Some(value) = self.#ident.to_config() {
+                        table.insert(stringify!(#ident).to_string(), value);
+                    }
+                };
+                let new_schema_tokens = quote! {
+                    properties.insert(stringify!(#ident).to_string(), <#ty>::schema());
+                };
+                let new_validate_tokens = quote! {
+                    stringify!(#ident) => {
+                        <#ty>::validate(value, &field_path, errors);
+                        #validate_style
+                    }
+                };
 
                 load_tokens = quote! {
                     #load_tokens
@@ -43,6 +70,18 @@ fn impl_module_config(dinput: DeriveInput) -> proc_macro::TokenStream {
                 from_tokens = quote! {
                     #from_tokens
                     #new_from_tokens
+                };
+                validate_tokens = quote! {
+                    #validate_tokens
+                    #new_validate_tokens
+                };
+                to_config_tokens = quote! {
+                    #to_config_tokens
+                    #new_to_config_tokens
+                };
+                schema_tokens = quote! {
+                    #schema_tokens
+                    #new_schema_tokens
                 }
             }
 
@@ -64,6 +103,48 @@ fn impl_module_config(dinput: DeriveInput) -> proc_macro::TokenStream {
                     })
                 }
             };
+            to_config = quote! {
+                fn to_config(&self) -> Option<toml::Value> {
+                    let mut table = toml::value::Table::new();
+                    #to_config_tokens
+                    Some(toml::Value::Table(table))
+                }
+            };
+            schema = quote! {
+                fn schema() -> serde_json::Value {
+                    let mut properties = serde_json::Map::new();
+                    #schema_tokens
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": properties,
+                        "additionalProperties": false,
+                    })
+                }
+            };
+            validate = quote! {
+                fn validate(
+                    config: &'a toml::Value,
+                    path: &[String],
+                    errors: &mut Vec<crate::config::ConfigError>,
+                ) {
+                    let config = match config.as_table() {
+                        Some(config) => config,
+                        None => {
+                            errors.push(crate::config::ConfigError::invalid_value(path, config));
+                            return;
+                        }
+                    };
+
+                    for (key, value) in config.iter() {
+                        let mut field_path = path.to_vec();
+                        field_path.push(key.clone());
+                        match key.as_str() {
+                            #validate_tokens
+                            _ => errors.push(crate::config::ConfigError::unknown_key(&field_path)),
+                        }
+                    }
+                }
+            };
         }
     }
 
@@ -71,6 +152,9 @@ fn impl_module_config(dinput: DeriveInput) -> proc_macro::TokenStream {
         impl<'a> ModuleConfig<'a> for #struct_ident #ty_generics #where_clause {
             #from_config
             #load_config
+            #validate
+            #to_config
+            #schema
         }
     })
 }
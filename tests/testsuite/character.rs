@@ -39,9 +39,9 @@ fn char_module_failure_status() -> io::Result<()> {
 }
 
 #[test]
-fn char_module_symbolyes_status() -> io::Result<()> {
+fn char_module_symbol() -> io::Result<()> {
     let expected_fail = format!("{} ", Color::Red.bold().paint("✖"));
-    let expected_success = format!("{} ", Color::Green.bold().paint("❯"));
+    let expected_success = format!("{} ", Color::Blue.bold().paint("➜"));
 
     let exit_values = ["1", "54321", "-5000"];
 
@@ -51,7 +51,8 @@ fn char_module_symbolyes_status() -> io::Result<()> {
         let output = common::render_module("character")
             .use_config(toml::toml! {
                 [character]
-                use_symbol_for_status = true
+                success_symbol = "[➜](bold blue)"
+                error_symbol = "[✖](bold red)"
             })
             .arg(arg)
             .output()?;
@@ -63,7 +64,8 @@ fn char_module_symbolyes_status() -> io::Result<()> {
     let output = common::render_module("character")
         .use_config(toml::toml! {
             [character]
-            use_symbol_for_status = true
+            success_symbol = "[➜](bold blue)"
+            error_symbol = "[✖](bold red)"
         })
         .arg("--status=0")
         .output()?;
@@ -111,6 +113,25 @@ fn char_module_zsh_keymap() -> io::Result<()> {
     Ok(())
 }
 
+#[test]
+fn char_module_xonsh_keymap() -> io::Result<()> {
+    let output = common::render_module("character")
+        .env("STARSHIP_SHELL", "xonsh")
+        .arg("--keymap=vicmd")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert!(actual.contains("❮"));
+
+    let output = common::render_module("character")
+        .env("STARSHIP_SHELL", "xonsh")
+        .arg("--keymap=viins")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert!(actual.contains("❯"));
+
+    Ok(())
+}
+
 #[test]
 fn char_module_fish_keymap() -> io::Result<()> {
     let expected_vicmd = "❮";
@@ -23,7 +23,8 @@ pub fn render_prompt() -> process::Command {
         .arg("prompt")
         .env_clear()
         .env("PATH", env!("PATH")) // Provide the $PATH variable so that external programs are runnable
-        .env("STARSHIP_CONFIG", EMPTY_CONFIG.as_os_str());
+        .env("STARSHIP_CONFIG", EMPTY_CONFIG.as_os_str())
+        .env("STARSHIP_SYSTEM_CONFIG", EMPTY_CONFIG.as_os_str());
 
     command
 }
@@ -38,7 +39,46 @@ pub fn render_module(module_name: &str) -> process::Command {
         .arg(module_name)
         .env_clear()
         .env("PATH", env!("PATH")) // Provide the $PATH variable so that external programs are runnable
-        .env("STARSHIP_CONFIG", EMPTY_CONFIG.as_os_str());
+        .env("STARSHIP_CONFIG", EMPTY_CONFIG.as_os_str())
+        .env("STARSHIP_SYSTEM_CONFIG", EMPTY_CONFIG.as_os_str());
+
+    command
+}
+
+/// Run a `starship config` subcommand, e.g. `config_command().arg("--check")`
+pub fn config_command() -> process::Command {
+    let binary = fs::canonicalize(EXE_PATH).unwrap();
+    let mut command = process::Command::new(binary);
+
+    command
+        .arg("config")
+        .env_clear()
+        .env("STARSHIP_CONFIG", EMPTY_CONFIG.as_os_str())
+        .env("STARSHIP_SYSTEM_CONFIG", EMPTY_CONFIG.as_os_str());
+
+    command
+}
+
+/// Run the `starship preset` subcommand, e.g. `preset_command().arg("--list")`
+pub fn preset_command() -> process::Command {
+    let binary = fs::canonicalize(EXE_PATH).unwrap();
+    let mut command = process::Command::new(binary);
+
+    command
+        .arg("preset")
+        .env_clear()
+        .env("STARSHIP_CONFIG", EMPTY_CONFIG.as_os_str())
+        .env("STARSHIP_SYSTEM_CONFIG", EMPTY_CONFIG.as_os_str());
+
+    command
+}
+
+/// Run the `starship init` subcommand for a shell, e.g. `init_command("zsh")`
+pub fn init_command(shell: &str) -> process::Command {
+    let binary = fs::canonicalize(EXE_PATH).unwrap();
+    let mut command = process::Command::new(binary);
+
+    command.arg("init").arg(shell).env_clear();
 
     command
 }
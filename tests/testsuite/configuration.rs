@@ -1,4 +1,5 @@
-use ansi_term::Color;
+use ansi_term::{ANSIStrings, Color, Style};
+use std::fs;
 use std::io;
 
 use crate::common::{self, TestCommand};
@@ -39,3 +40,991 @@ fn add_newline_configuration() -> io::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn root_format_configuration() -> io::Result<()> {
+    let output = common::render_prompt()
+        .use_config(toml::toml! {
+            add_newline = false
+            format = "[starship](red) $line_break$character"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = format!(
+        "{} \n{} ",
+        Color::Red.paint("starship"),
+        Color::Green.bold().paint("❯")
+    );
+    assert_eq!(expected, actual);
+
+    Ok(())
+}
+
+#[test]
+fn root_format_all_skips_listed_modules() -> io::Result<()> {
+    let output = common::render_prompt()
+        .use_config(toml::toml! {
+            add_newline = false
+            format = "$all$character"
+            [line_break]
+            disabled = true
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let character = format!("{} ", Color::Green.bold().paint("❯"));
+    assert!(actual.ends_with(&character));
+    assert_eq!(actual.matches("❯").count(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn right_format_configuration() -> io::Result<()> {
+    let output = common::render_prompt()
+        .arg("--right")
+        .use_config(toml::toml! {
+            format = "$all"
+            right_format = "[starship](red) $character"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = format!(
+        "{} {} ",
+        Color::Red.paint("starship"),
+        Color::Green.bold().paint("❯")
+    );
+    assert_eq!(expected, actual);
+
+    // Modules used in the right prompt are left out of $all
+    let output = common::render_prompt()
+        .use_config(toml::toml! {
+            add_newline = false
+            format = "$all"
+            right_format = "$character"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert!(!actual.contains("❯"));
+
+    Ok(())
+}
+
+#[test]
+fn powershell_right_prompt() -> io::Result<()> {
+    let render = |width: &str| {
+        common::render_prompt()
+            .arg("--right")
+            .arg(format!("--terminal-width={}", width))
+            .env("STARSHIP_SHELL", "powershell")
+            .use_config(toml::toml! {
+                right_format = "[👋 starship](red)"
+            })
+            .output()
+    };
+
+    // The wide character takes two columns
+    let actual = String::from_utf8(render("20")?.stdout).unwrap();
+    let expected = format!("\x1b[s\x1b[10G{}\x1b[u", Color::Red.paint("👋 starship"));
+    assert_eq!(expected, actual);
+
+    // A right prompt wider than the terminal isn't drawn
+    let actual = String::from_utf8(render("10")?.stdout).unwrap();
+    assert_eq!("", actual);
+    Ok(())
+}
+
+#[test]
+fn check_valid_configuration() -> io::Result<()> {
+    let output = common::config_command()
+        .arg("--check")
+        .use_config(toml::toml! {
+            format = "$directory$character"
+            [directory]
+            truncation_length = 2
+            style = "bold cyan"
+            priority = 10
+            [custom.foo]
+            command = "echo foo"
+            priority = 20
+        })
+        .output()?;
+
+    assert!(output.status.success());
+    Ok(())
+}
+
+#[test]
+fn check_invalid_configuration() -> io::Result<()> {
+    let output = common::config_command()
+        .arg("--check")
+        .use_config(toml::toml! {
+            [git_branch]
+            style = "bold purpel"
+            trunction_length = 4
+            priority = "high"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stderr).unwrap();
+
+    assert!(!output.status.success());
+    assert!(actual.contains("invalid style string \"bold purpel\" for `git_branch.style`"));
+    assert!(actual.contains("unknown key `git_branch.trunction_length`"));
+    assert!(actual.contains("invalid value \"high\" for `git_branch.priority`"));
+    Ok(())
+}
+
+#[test]
+fn check_every_configuration_file() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let missing_path = dir.path().join("missing.toml");
+
+    // Missing configuration files are optional
+    let output = common::config_command()
+        .arg("--check")
+        .env("STARSHIP_CONFIG", &missing_path)
+        .env("STARSHIP_SYSTEM_CONFIG", &missing_path)
+        .current_dir(dir.path())
+        .output()?;
+    assert!(output.status.success());
+
+    fs::write(
+        dir.path().join(".starship.toml"),
+        "[git_branch]\ntrunction_length = 4\n",
+    )?;
+    let output = common::config_command()
+        .arg("--check")
+        .env("STARSHIP_CONFIG", &missing_path)
+        .current_dir(dir.path())
+        .output()?;
+    let actual = String::from_utf8(output.stderr).unwrap();
+    assert!(!output.status.success());
+    assert!(actual.contains(".starship.toml:2: unknown key `git_branch.trunction_length`"));
+    dir.close()
+}
+
+#[test]
+fn check_configuration_syntax_error() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let config_path = dir.path().join("starship.toml");
+    fs::write(&config_path, "[git_branch\nstyle = \"red\"\n")?;
+
+    let output = common::config_command()
+        .arg("--check")
+        .env("STARSHIP_CONFIG", &config_path)
+        .output()?;
+    let actual = String::from_utf8(output.stderr).unwrap();
+    assert!(!output.status.success());
+    // The error is only reported once, by the check itself
+    assert_eq!(actual.matches("starship.toml").count(), 1, "{}", actual);
+    dir.close()
+}
+
+#[test]
+fn print_config_schema() -> io::Result<()> {
+    let output = common::config_command().arg("--schema").output()?;
+    let schema: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    assert_eq!(schema["properties"]["add_newline"]["default"], true);
+    assert_eq!(
+        schema["properties"]["git_branch"]["properties"]["truncation_length"]["type"],
+        "integer"
+    );
+    assert_eq!(
+        schema["properties"]["directory"]["properties"]["priority"]["default"],
+        90
+    );
+    assert_eq!(
+        schema["properties"]["custom"]["additionalProperties"]["properties"]["shell"]["anyOf"][1]
+            ["type"],
+        "array"
+    );
+    Ok(())
+}
+
+#[test]
+fn print_default_configuration() -> io::Result<()> {
+    let output = common::config_command()
+        .arg("--print-defaults")
+        .use_config(toml::toml! {
+            add_newline = false
+            [git_branch]
+            style = "bold red"
+            [custom.foo]
+            command = "echo foo"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    assert!(actual.contains("add_newline = false # user\n"));
+    assert!(actual.contains("scan_timeout = 30 # default\n"));
+    assert!(actual.contains("[git_branch]\nformat = \"on [$symbol$branch]($style) \" # default\n"));
+    assert!(actual.contains("style = \"bold red\" # user\n"));
+    assert!(actual.contains("[custom.foo]\n"));
+    assert!(actual.contains("command = \"echo foo\" # user\n"));
+
+    // Strip the annotations and check the result is a valid config
+    let config = actual
+        .lines()
+        .map(|line| line.rsplitn(2, " # ").last().unwrap())
+        .collect::<Vec<_>>()
+        .join("\n");
+    assert!(toml::from_str::<toml::Value>(&config).is_ok());
+    Ok(())
+}
+
+#[test]
+fn project_configuration() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let sub_dir = dir.path().join("sub");
+    fs::create_dir(&sub_dir)?;
+    fs::write(
+        dir.path().join(".starship.toml"),
+        "[character]\nsuccess_symbol = \"[P](bold green)\"\n",
+    )?;
+
+    let output = common::render_module("character")
+        .use_config(toml::toml! {
+            [character]
+            success_symbol = "[U](bold green)"
+            error_symbol = "[E](bold red)"
+        })
+        .arg("--path")
+        .arg(&sub_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("{} ", Color::Green.bold().paint("P"));
+    assert_eq!(expected, actual);
+    dir.close()
+}
+
+#[test]
+fn untrusted_project_configuration() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let project_dir = dir.path().join("project");
+    let sub_dir = project_dir.join("sub");
+    fs::create_dir_all(&sub_dir)?;
+    fs::write(dir.path().join("shared.toml"), "format = \"shared\"\n")?;
+    fs::write(
+        project_dir.join(".starship.toml"),
+        "include = \"../shared.toml\"\n\
+         trusted_projects = [\".\"]\n\
+         format = \"$custom\"\n\
+         [custom.x]\ncommand = \"echo ran\"\nwhen = \"true\"\nformat = \"$output\"\n",
+    )?;
+
+    let render = |config: toml::Value| {
+        common::render_prompt()
+            .use_config(config)
+            .arg("--path")
+            .arg(&sub_dir)
+            .output()
+    };
+
+    // Neither the custom module nor the include of an untrusted project is used
+    let output = render(toml::toml! {
+        add_newline = false
+    })?;
+    assert_eq!("", String::from_utf8(output.stdout).unwrap());
+
+    let trusted_dir = project_dir.to_string_lossy().to_string();
+    let output = render(toml::toml! {
+        add_newline = false
+        trusted_projects = [(trusted_dir)]
+    })?;
+    assert_eq!("ran", String::from_utf8(output.stdout).unwrap());
+
+    let output = common::config_command()
+        .arg("--check")
+        .current_dir(&sub_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stderr).unwrap();
+    assert!(!output.status.success());
+    assert!(actual.contains(".starship.toml:1: `include` is ignored"));
+    assert!(actual.contains(".starship.toml: `custom` is ignored"));
+    dir.close()
+}
+
+#[test]
+fn env_override_configuration() -> io::Result<()> {
+    let output = common::render_module("character")
+        .use_config(toml::toml! {
+            [character]
+            success_symbol = "[U](bold green)"
+        })
+        .env("STARSHIP__character__success_symbol", "[V](bold red)")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("{} ", Color::Red.bold().paint("V"));
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn include_configuration() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let config_path = dir.path().join("starship.toml");
+    fs::write(
+        &config_path,
+        "include = [\"team.toml\", \"missing.toml\"]\n\
+         [character]\nsuccess_symbol = \"[U](bold green)\"\n",
+    )?;
+    fs::write(
+        dir.path().join("team.toml"),
+        "include = \"starship.toml\"\n\
+         [character]\nsuccess_symbol = \"[T](bold red)\"\nformat = \"<$symbol>\"\n",
+    )?;
+
+    let output = common::render_module("character")
+        .env("STARSHIP_CONFIG", &config_path)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("<{}>", Color::Green.bold().paint("U"));
+    assert_eq!(expected, actual);
+    dir.close()
+}
+
+#[test]
+fn palette_configuration() -> io::Result<()> {
+    let output = common::render_module("character")
+        .use_config(toml::toml! {
+            palette = "mine"
+            [palettes.mine]
+            accent = "#ff8000"
+            [character]
+            success_symbol = "[❯](bold fg:accent bg:surface)"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    // `surface` isn't defined, so the style string is ignored
+    let expected = "❯ ";
+    assert_eq!(expected, actual);
+
+    let output = common::render_module("character")
+        .use_config(toml::toml! {
+            palette = "mine"
+            [palettes.mine]
+            accent = "#ff8000"
+            surface = "238"
+            [character]
+            success_symbol = "[❯](bold fg:accent bg:surface)"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!(
+        "{} ",
+        Color::RGB(255, 128, 0)
+            .bold()
+            .on(Color::Fixed(238))
+            .paint("❯")
+    );
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn check_palette_configuration() -> io::Result<()> {
+    let output = common::config_command()
+        .arg("--check")
+        .use_config(toml::toml! {
+            palette = "mine"
+            [palettes.mine]
+            accent = "#ff8000"
+            [git_branch]
+            style = "bold accent"
+        })
+        .output()?;
+    assert!(output.status.success());
+
+    let output = common::config_command()
+        .arg("--check")
+        .use_config(toml::toml! {
+            palette = "missing"
+            [palettes.mine]
+            accent = "#ff8000"
+            [git_branch]
+            style = "bold accent"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stderr).unwrap();
+
+    assert!(!output.status.success());
+    assert!(actual.contains("palette `missing` is not defined in `palettes`"));
+    assert!(actual.contains("invalid style string \"bold accent\" for `git_branch.style`"));
+    Ok(())
+}
+
+#[test]
+fn color_depth_configuration() -> io::Result<()> {
+    let config = toml::toml! {
+        [character]
+        success_symbol = "[❯](bold fg:#ff8000)"
+    };
+
+    let output = common::render_module("character")
+        .use_config(config.clone())
+        .env("TERM", "xterm-256color")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = format!("{} ", Color::Fixed(208).bold().paint("❯"));
+    assert_eq!(expected, actual);
+
+    let output = common::render_module("character")
+        .use_config(config)
+        .env("TERM", "linux")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = format!("{} ", Color::Yellow.bold().paint("❯"));
+    assert_eq!(expected, actual);
+
+    let output = common::render_module("character")
+        .use_config(toml::toml! {
+            color_depth = "truecolor"
+            [character]
+            success_symbol = "[❯](bold fg:#ff8000)"
+        })
+        .env("TERM", "linux")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = format!("{} ", Color::RGB(255, 128, 0).bold().paint("❯"));
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn no_color_configuration() -> io::Result<()> {
+    let output = common::render_module("character")
+        .env("NO_COLOR", "1")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = format!("{} ", Style::new().bold().paint("❯"));
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn theme_configuration() -> io::Result<()> {
+    let config = toml::toml! {
+        [character]
+        success_symbol = "[❯](bold green)"
+        [theme.light.character]
+        success_symbol = "[❯](bold blue)"
+        [theme.dark.character]
+        success_symbol = "[❯](bold yellow)"
+    };
+
+    let output = common::render_module("character")
+        .use_config(config.clone())
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = format!("{} ", Color::Green.bold().paint("❯"));
+    assert_eq!(expected, actual);
+
+    let output = common::render_module("character")
+        .use_config(config.clone())
+        .env("COLORFGBG", "0;15")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = format!("{} ", Color::Blue.bold().paint("❯"));
+    assert_eq!(expected, actual);
+
+    let output = common::render_module("character")
+        .use_config(config)
+        .env("COLORFGBG", "0;15")
+        .env("STARSHIP_THEME", "dark")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = format!("{} ", Color::Yellow.bold().paint("❯"));
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn check_theme_configuration() -> io::Result<()> {
+    let output = common::config_command()
+        .arg("--check")
+        .use_config(toml::toml! {
+            [theme]
+            mode = "dusk"
+            [theme.dark.git_branch]
+            style = "bold yelow"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stderr).unwrap();
+
+    assert!(!output.status.success());
+    assert!(actual.contains("invalid value \"dusk\" for `theme.mode`"));
+    assert!(
+        actual.contains("invalid style string \"bold yelow\" for `theme.dark.git_branch.style`")
+    );
+    Ok(())
+}
+
+#[test]
+fn powerline_configuration() -> io::Result<()> {
+    let config = toml::toml! {
+        add_newline = false
+        format = "${custom.a}${custom.b}"
+        right_format = "${custom.a}${custom.b}"
+        powerline = true
+        [custom.a]
+        command = "echo a"
+        when = "true"
+        format = "[$output](bg:blue)"
+        [custom.b]
+        command = "echo b"
+        when = "true"
+        format = "[$output](black bg:green)"
+    };
+
+    let output = common::render_prompt()
+        .use_config(config.clone())
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = ANSIStrings(&[
+        Style::new().on(Color::Blue).paint("a"),
+        Color::Blue.on(Color::Green).paint("\u{e0b0}"),
+        Color::Black.on(Color::Green).paint("b"),
+        Color::Green.paint("\u{e0b0}"),
+    ])
+    .to_string();
+    assert_eq!(expected, actual);
+
+    let output = common::render_prompt()
+        .arg("--right")
+        .use_config(config)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = ANSIStrings(&[
+        Color::Blue.paint("\u{e0b2}"),
+        Style::new().on(Color::Blue).paint("a"),
+        Color::Green.on(Color::Blue).paint("\u{e0b2}"),
+        Color::Black.on(Color::Green).paint("b"),
+    ])
+    .to_string();
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn list_presets() -> io::Result<()> {
+    let output = common::preset_command().arg("--list").output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success());
+    assert!(actual.contains("nerd-font-symbols"));
+    assert!(actual.contains("pure-preset"));
+    Ok(())
+}
+
+#[test]
+fn when_configuration() -> io::Result<()> {
+    let config = toml::toml! {
+        [character]
+        success_symbol = "[>](green)"
+
+        [[when]]
+        shell = "bash"
+        env = { CI = "*" }
+        [when.character]
+        success_symbol = "[ci>](yellow)"
+
+        [[when]]
+        hostname = "no-such-host-*"
+        [when.character]
+        success_symbol = "[prod>](red)"
+    };
+
+    let output = common::render_module("character")
+        .use_config(config.clone())
+        .env("STARSHIP_SHELL", "bash")
+        .env("CI", "true")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = format!("{} ", Color::Yellow.paint("ci>"));
+    assert_eq!(expected, actual);
+
+    let output = common::render_module("character")
+        .use_config(config)
+        .env("STARSHIP_SHELL", "zsh")
+        .env("CI", "true")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = format!("{} ", Color::Green.paint(">"));
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn check_when_configuration() -> io::Result<()> {
+    let output = common::config_command()
+        .arg("--check")
+        .use_config(toml::toml! {
+            [[when]]
+            shell = 1
+            [when.character]
+            sucess_symbol = ">"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stderr).unwrap();
+    assert!(!output.status.success());
+    assert!(actual.contains("invalid value 1 for `when.0.shell`"));
+    assert!(actual.contains("unknown key `when.0.character.sucess_symbol`"));
+    Ok(())
+}
+
+#[test]
+fn edit_configuration_keys() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let config_path = dir.path().join("starship.toml");
+    let original = "# Prompt\nadd_newline = false # short\n\n[character]\nsuccess_symbol = \">\"\n";
+    fs::write(&config_path, original)?;
+
+    let run = |args: &[&str]| {
+        common::config_command()
+            .args(args)
+            .env("STARSHIP_CONFIG", &config_path)
+            .output()
+    };
+
+    for args in &[
+        &["set", "add_newline", "true"][..],
+        &["set", "battery.display[0]", "{ threshold = 10 }"],
+        &["set", "battery.display[0].style", "bold red"],
+        &["set", "custom.foo.files", "[\"foo.txt\"]"],
+        &["unset", "character.success_symbol"],
+        // The original form only takes a key and a value
+        &["git_branch.symbol", "git "],
+    ] {
+        let output = run(args)?;
+        assert!(output.status.success(), "{:?}", args);
+    }
+
+    let expected = r#"# Prompt
+add_newline = true # short
+
+[character]
+
+[[battery.display]]
+threshold = 10
+style = "bold red"
+
+[custom.foo]
+files = ["foo.txt"]
+
+[git_branch]
+symbol = "git "
+"#;
+    assert_eq!(fs::read_to_string(&config_path)?, expected);
+    assert!(dir.path().join("starship.toml.bak").exists());
+
+    let output = run(&["get", "battery.display[0].style"])?;
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "bold red\n");
+    let output = run(&["get", "custom.foo.files"])?;
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "[\"foo.txt\"]\n");
+    let output = run(&["get", "character.success_symbol"])?;
+    assert!(!output.status.success());
+
+    let output = run(&["list"])?;
+    let expected = r#"add_newline = true
+battery.display[0].threshold = 10
+battery.display[0].style = "bold red"
+custom.foo.files = ["foo.txt"]
+git_branch.symbol = "git "
+"#;
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), expected);
+
+    let output = run(&["set", "add_newline.foo", "1"])?;
+    assert!(!output.status.success());
+    let output = run(&["set", "battery..style", "red"])?;
+    assert!(!output.status.success());
+    dir.close()
+}
+
+#[cfg(unix)]
+#[test]
+fn edit_configuration_through_symlink() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let config_path = dir.path().join("dotfiles.toml");
+    let link_path = dir.path().join("starship.toml");
+    fs::write(&config_path, "add_newline = false\n")?;
+    std::os::unix::fs::symlink(&config_path, &link_path)?;
+
+    let output = common::config_command()
+        .args(["set", "add_newline", "true"])
+        .env("STARSHIP_CONFIG", &link_path)
+        .output()?;
+    assert!(output.status.success());
+    assert!(fs::symlink_metadata(&link_path)?.file_type().is_symlink());
+    assert_eq!(fs::read_to_string(&config_path)?, "add_newline = true\n");
+    dir.close()
+}
+
+#[test]
+fn migrate_configuration() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let config_path = dir.path().join("starship.toml");
+    let original = "# Prompt\n[cmd_duration]\nprefix = \"took \" # old\n";
+    fs::write(&config_path, original)?;
+
+    let run = |args: &[&str]| {
+        common::config_command()
+            .args(args)
+            .env("STARSHIP_CONFIG", &config_path)
+            .output()
+    };
+
+    // Only print the changes by default
+    let output = run(&["migrate"])?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success());
+    assert!(actual.contains("`cmd_duration.prefix` was replaced by `format` in 0.44.0"));
+    assert!(actual.contains("-prefix = \"took \" # old\n+format = \"took [$duration]($style) \"\n"));
+    assert_eq!(fs::read_to_string(&config_path)?, original);
+
+    let output = run(&["--check"])?;
+    let actual = String::from_utf8(output.stderr).unwrap();
+    assert!(!output.status.success());
+    assert!(actual.contains(":3: `cmd_duration.prefix` was replaced by `format` in 0.44.0"));
+
+    let output = run(&["migrate", "--write"])?;
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(&config_path)?,
+        "# Prompt\n[cmd_duration]\nformat = \"took [$duration]($style) \"\n"
+    );
+    assert_eq!(
+        fs::read_to_string(dir.path().join("starship.toml.bak"))?,
+        original
+    );
+
+    let output = run(&["--check"])?;
+    assert!(output.status.success());
+    dir.close()
+}
+
+#[test]
+fn deprecated_configuration_notice() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    fs::write(
+        dir.path().join(".starship.toml"),
+        "[cmd_duration]\nprefix = \"took \"\n",
+    )?;
+
+    // STARSHIP_LOG isn't set, and the key replaced in both files is only reported once
+    let output = common::render_module("cmd_duration")
+        .arg("--cmd-duration=6000")
+        .arg("--path")
+        .arg(dir.path())
+        .use_config(toml::toml! {
+            [cmd_duration]
+            prefix = "took "
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stderr).unwrap();
+    let notice = "`cmd_duration.prefix` was replaced by `format` in 0.44.0";
+    assert_eq!(actual.matches(notice).count(), 1, "{}", actual);
+    dir.close()
+}
+
+#[test]
+fn migrate_prompt_order() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let config_path = dir.path().join("starship.toml");
+    fs::write(
+        &config_path,
+        "prompt_order = [\"directory\", \"character\"]\n",
+    )?;
+
+    let output = common::config_command()
+        .args(["migrate", "--write"])
+        .env("STARSHIP_CONFIG", &config_path)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success());
+    assert!(actual.contains("`prompt_order` was replaced by `format` in 0.44.0"));
+    assert_eq!(
+        fs::read_to_string(&config_path)?,
+        "format = \"$directory$character\"\n"
+    );
+    dir.close()
+}
+
+#[test]
+fn write_preset() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let config_path = dir.path().join("starship.toml");
+    fs::write(&config_path, "add_newline = false\n")?;
+
+    let output = common::preset_command()
+        .arg("plain-text-symbols")
+        .output()?;
+    let printed = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success());
+
+    for _ in 0..2 {
+        let output = common::preset_command()
+            .args(["plain-text-symbols", "--write"])
+            .env("STARSHIP_CONFIG", &config_path)
+            .output()?;
+        assert!(output.status.success());
+    }
+
+    // The original file survives both writes
+    assert_eq!(fs::read_to_string(&config_path)?, printed);
+    assert_eq!(
+        fs::read_to_string(dir.path().join("starship.toml.bak"))?,
+        "add_newline = false\n"
+    );
+    assert_eq!(
+        fs::read_to_string(dir.path().join("starship.toml.bak.1"))?,
+        printed
+    );
+    assert!(!dir.path().join("starship.toml.tmp").exists());
+    dir.close()
+}
+
+#[test]
+fn preset_configuration() -> io::Result<()> {
+    let output = common::render_module("character")
+        .use_config(toml::toml! {
+            preset = "plain-text-symbols"
+            [character]
+            error_symbol = "[!](bold red)"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = format!("{} ", Color::Green.bold().paint(">"));
+    assert_eq!(expected, actual);
+
+    let output = common::config_command()
+        .arg("--check")
+        .use_config(toml::toml! {
+            preset = "unknown"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stderr).unwrap();
+    assert!(!output.status.success());
+    assert!(actual.contains("invalid value \"unknown\" for `preset`"));
+    Ok(())
+}
+
+#[test]
+fn symbols_configuration() -> io::Result<()> {
+    let output = common::render_module("character")
+        .use_config(toml::toml! {
+            symbols = "ascii"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = format!("{} ", Color::Green.bold().paint(">"));
+    assert_eq!(expected, actual);
+
+    // An explicitly configured symbol wins over the symbol set
+    let output = common::render_module("character")
+        .use_config(toml::toml! {
+            symbols = "ascii"
+            [character]
+            success_symbol = "[#](bold green)"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = format!("{} ", Color::Green.bold().paint("#"));
+    assert_eq!(expected, actual);
+
+    let output = common::config_command()
+        .arg("--check")
+        .use_config(toml::toml! {
+            symbols = "emoji"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stderr).unwrap();
+    assert!(!output.status.success());
+    assert!(actual.contains("invalid value \"emoji\" for `symbols`"));
+    Ok(())
+}
+
+#[test]
+fn redact_configuration() -> io::Result<()> {
+    let output = common::render_prompt()
+        .use_config(toml::toml! {
+            add_newline = false
+            redact = true
+            format = "$env_var$custom"
+            [env_var]
+            variable = "TEST_VAR"
+            format = "$env_value "
+            [custom.projects]
+            command = "echo secret"
+            when = "true"
+            format = "$output"
+        })
+        .env("TEST_VAR", "secret")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!("*** ***", actual);
+    Ok(())
+}
+
+#[test]
+fn transient_prompt() -> io::Result<()> {
+    let config = toml::toml! {
+        add_newline = false
+        format = "[starship](red) $line_break$character"
+        right_format = "[right](blue)"
+    };
+
+    let output = common::render_prompt()
+        .arg("--transient")
+        .use_config(config.clone())
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = format!("{} ", Color::Green.bold().paint("❯"));
+    assert_eq!(expected, actual);
+
+    // The right prompt is empty by default
+    let output = common::render_prompt()
+        .args(["--transient", "--right"])
+        .use_config(config)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!("", actual);
+
+    let output = common::render_prompt()
+        .args(["--transient", "--right"])
+        .use_config(toml::toml! {
+            transient_right_format = "$time"
+            [time]
+            disabled = false
+            format = "at $time"
+            time_format = "%Y"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert!(actual.starts_with("at "));
+    Ok(())
+}
+
+#[test]
+fn continuation_prompt() -> io::Result<()> {
+    let output = common::render_prompt().arg("--continuation").output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = format!("{} ", Color::Fixed(8).paint("∙"));
+    assert_eq!(expected, actual);
+
+    // The colors are wrapped for the shell, and no newline is added before the prompt
+    let output = common::render_prompt()
+        .arg("--continuation")
+        .env("STARSHIP_SHELL", "bash")
+        .use_config(toml::toml! {
+            continuation_prompt = "[>>](red) "
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!("\\[\u{1b}[31m\\]>>\\[\u{1b}[0m\\] ", actual);
+    Ok(())
+}
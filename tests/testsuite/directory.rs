@@ -23,7 +23,7 @@ fn home_directory() -> io::Result<()> {
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
 
-    let expected = format!("in {} ", Color::Cyan.bold().paint("~"));
+    let expected = format!("{} ", Color::Cyan.bold().paint("~"));
     assert_eq!(expected, actual);
     Ok(())
 }
@@ -42,7 +42,7 @@ fn substituted_truncated_path() -> io::Result<()> {
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
 
-    let expected = format!("in {} ", Color::Cyan.bold().paint("net/workspace/d/dev"));
+    let expected = format!("{} ", Color::Cyan.bold().paint("net/workspace/d/dev"));
     assert_eq!(expected, actual);
     Ok(())
 }
@@ -63,7 +63,7 @@ fn strange_substitution() -> io::Result<()> {
     let actual = String::from_utf8(output.stdout).unwrap();
 
     let expected = format!(
-        "in {} ",
+        "{} ",
         Color::Cyan
             .bold()
             .paint(format!("/foo/bar/{}/path", strange_sub))
@@ -84,7 +84,7 @@ fn directory_in_home() -> io::Result<()> {
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
 
-    let expected = format!("in {} ", Color::Cyan.bold().paint("~/starship/engine"));
+    let expected = format!("{} ", Color::Cyan.bold().paint("~/starship/engine"));
     assert_eq!(expected, actual);
     Ok(())
 }
@@ -102,7 +102,7 @@ fn truncated_directory_in_home() -> io::Result<()> {
     let actual = String::from_utf8(output.stdout).unwrap();
 
     let expected = format!(
-        "in {} ",
+        "{} ",
         Color::Cyan.bold().paint("starship/engine/schematics")
     );
     assert_eq!(expected, actual);
@@ -126,7 +126,7 @@ fn fish_directory_in_home() -> io::Result<()> {
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
 
-    let expected = format!("in {} ", Color::Cyan.bold().paint("~/st/en/schematics"));
+    let expected = format!("{} ", Color::Cyan.bold().paint("~/st/en/schematics"));
     assert_eq!(expected, actual);
     Ok(())
 }
@@ -138,7 +138,7 @@ fn root_directory() -> io::Result<()> {
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
 
-    let expected = format!("in {} ", Color::Cyan.bold().paint("/"));
+    let expected = format!("{} ", Color::Cyan.bold().paint("/"));
     assert_eq!(expected, actual);
     Ok(())
 }
@@ -149,7 +149,7 @@ fn test_prefix() -> io::Result<()> {
         .arg("--path=/")
         .use_config(toml::toml! {
             [directory]
-            prefix = "sample "
+            format = "sample [$path]($style) "
         })
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
@@ -167,7 +167,7 @@ fn directory_in_root() -> io::Result<()> {
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
 
-    let expected = format!("in {} ", Color::Cyan.bold().paint("/etc"));
+    let expected = format!("{} ", Color::Cyan.bold().paint("/etc"));
     assert_eq!(expected, actual);
     Ok(())
 }
@@ -180,7 +180,7 @@ fn directory_in_root() -> io::Result<()> {
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
 
-    let expected = format!("in {} ", Color::Cyan.bold().paint("C:"));
+    let expected = format!("{} ", Color::Cyan.bold().paint("C:"));
     assert_eq!(expected, actual);
     Ok(())
 }
@@ -197,10 +197,7 @@ fn truncated_directory_in_root() -> io::Result<()> {
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
 
-    let expected = format!(
-        "in {} ",
-        Color::Cyan.bold().paint("starship/thrusters/rocket")
-    );
+    let expected = format!("{} ", Color::Cyan.bold().paint("starship/thrusters/rocket"));
     assert_eq!(expected, actual);
     Ok(())
 }
@@ -222,7 +219,7 @@ fn truncated_directory_config_large() -> io::Result<()> {
     let actual = String::from_utf8(output.stdout).unwrap();
 
     let expected = format!(
-        "in {} ",
+        "{} ",
         Color::Cyan.bold().paint("/tmp/starship/thrusters/rocket")
     );
     assert_eq!(expected, actual);
@@ -247,7 +244,7 @@ fn fish_style_directory_config_large() -> io::Result<()> {
     let actual = String::from_utf8(output.stdout).unwrap();
 
     let expected = format!(
-        "in {} ",
+        "{} ",
         Color::Cyan.bold().paint("/tmp/starship/thrusters/rocket")
     );
     assert_eq!(expected, actual);
@@ -270,7 +267,7 @@ fn truncated_directory_config_small() -> io::Result<()> {
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
 
-    let expected = format!("in {} ", Color::Cyan.bold().paint("thrusters/rocket"));
+    let expected = format!("{} ", Color::Cyan.bold().paint("thrusters/rocket"));
     assert_eq!(expected, actual);
     Ok(())
 }
@@ -292,7 +289,7 @@ fn fish_directory_config_small() -> io::Result<()> {
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
 
-    let expected = format!("in {} ", Color::Cyan.bold().paint("/t/s/thrusters/rocket"));
+    let expected = format!("{} ", Color::Cyan.bold().paint("/t/s/thrusters/rocket"));
     assert_eq!(expected, actual);
     Ok(())
 }
@@ -314,7 +311,7 @@ fn git_repo_root() -> io::Result<()> {
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
 
-    let expected = format!("in {} ", Color::Cyan.bold().paint("rocket-controls"));
+    let expected = format!("{} ", Color::Cyan.bold().paint("rocket-controls"));
     assert_eq!(expected, actual);
     tmp_dir.close()
 }
@@ -334,7 +331,7 @@ fn directory_in_git_repo() -> io::Result<()> {
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
 
-    let expected = format!("in {} ", Color::Cyan.bold().paint("rocket-controls/src"));
+    let expected = format!("{} ", Color::Cyan.bold().paint("rocket-controls/src"));
     assert_eq!(expected, actual);
     tmp_dir.close()
 }
@@ -354,7 +351,7 @@ fn truncated_directory_in_git_repo() -> io::Result<()> {
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
 
-    let expected = format!("in {} ", Color::Cyan.bold().paint("src/meters/fuel-gauge"));
+    let expected = format!("{} ", Color::Cyan.bold().paint("src/meters/fuel-gauge"));
     assert_eq!(expected, actual);
     tmp_dir.close()
 }
@@ -381,7 +378,7 @@ fn directory_in_git_repo_truncate_to_repo_false() -> io::Result<()> {
     let actual = String::from_utf8(output.stdout).unwrap();
 
     let expected = format!(
-        "in {} ",
+        "{} ",
         Color::Cyan
             .bold()
             .paint("above-repo/rocket-controls/src/meters/fuel-gauge")
@@ -413,7 +410,7 @@ fn fish_path_directory_in_git_repo_truncate_to_repo_false() -> io::Result<()> {
     let actual = String::from_utf8(output.stdout).unwrap();
 
     let expected = format!(
-        "in {} ",
+        "{} ",
         Color::Cyan
             .bold()
             .paint("~/.t/above-repo/rocket-controls/src/meters/fuel-gauge")
@@ -445,7 +442,7 @@ fn fish_path_directory_in_git_repo_truncate_to_repo_true() -> io::Result<()> {
     let actual = String::from_utf8(output.stdout).unwrap();
 
     let expected = format!(
-        "in {} ",
+        "{} ",
         Color::Cyan
             .bold()
             .paint("~/.t/a/rocket-controls/src/meters/fuel-gauge")
@@ -476,7 +473,7 @@ fn directory_in_git_repo_truncate_to_repo_true() -> io::Result<()> {
     let actual = String::from_utf8(output.stdout).unwrap();
 
     let expected = format!(
-        "in {} ",
+        "{} ",
         Color::Cyan
             .bold()
             .paint("rocket-controls/src/meters/fuel-gauge")
@@ -508,10 +505,7 @@ fn git_repo_in_home_directory_truncate_to_repo_true() -> io::Result<()> {
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
 
-    let expected = format!(
-        "in {} ",
-        Color::Cyan.bold().paint("~/src/meters/fuel-gauge")
-    );
+    let expected = format!("{} ", Color::Cyan.bold().paint("~/src/meters/fuel-gauge"));
     assert_eq!(expected, actual);
     tmp_dir.close()
 }
@@ -532,10 +526,7 @@ fn symlinked_git_repo_root() -> io::Result<()> {
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
 
-    let expected = format!(
-        "in {} ",
-        Color::Cyan.bold().paint("rocket-controls-symlink")
-    );
+    let expected = format!("{} ", Color::Cyan.bold().paint("rocket-controls-symlink"));
     assert_eq!(expected, actual);
     tmp_dir.close()
 }
@@ -559,7 +550,7 @@ fn directory_in_symlinked_git_repo() -> io::Result<()> {
     let actual = String::from_utf8(output.stdout).unwrap();
 
     let expected = format!(
-        "in {} ",
+        "{} ",
         Color::Cyan.bold().paint("rocket-controls-symlink/src")
     );
     assert_eq!(expected, actual);
@@ -584,7 +575,7 @@ fn truncated_directory_in_symlinked_git_repo() -> io::Result<()> {
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
 
-    let expected = format!("in {} ", Color::Cyan.bold().paint("src/meters/fuel-gauge"));
+    let expected = format!("{} ", Color::Cyan.bold().paint("src/meters/fuel-gauge"));
     assert_eq!(expected, actual);
     tmp_dir.close()
 }
@@ -617,7 +608,7 @@ fn directory_in_symlinked_git_repo_truncate_to_repo_false() -> io::Result<()> {
     let actual = String::from_utf8(output.stdout).unwrap();
 
     let expected = format!(
-        "in {} ",
+        "{} ",
         Color::Cyan
             .bold()
             .paint("above-repo/rocket-controls-symlink/src/meters/fuel-gauge")
@@ -655,7 +646,7 @@ fn fish_path_directory_in_symlinked_git_repo_truncate_to_repo_false() -> io::Res
     let actual = String::from_utf8(output.stdout).unwrap();
 
     let expected = format!(
-        "in {} ",
+        "{} ",
         Color::Cyan
             .bold()
             .paint("~/.t/above-repo/rocket-controls-symlink/src/meters/fuel-gauge")
@@ -693,7 +684,7 @@ fn fish_path_directory_in_symlinked_git_repo_truncate_to_repo_true() -> io::Resu
     let actual = String::from_utf8(output.stdout).unwrap();
 
     let expected = format!(
-        "in {} ",
+        "{} ",
         Color::Cyan
             .bold()
             .paint("~/.t/a/rocket-controls-symlink/src/meters/fuel-gauge")
@@ -730,7 +721,7 @@ fn directory_in_symlinked_git_repo_truncate_to_repo_true() -> io::Result<()> {
     let actual = String::from_utf8(output.stdout).unwrap();
 
     let expected = format!(
-        "in {} ",
+        "{} ",
         Color::Cyan
             .bold()
             .paint("rocket-controls-symlink/src/meters/fuel-gauge")
@@ -762,7 +753,7 @@ fn symlinked_directory_in_git_repo() -> io::Result<()> {
     let actual = String::from_utf8(output.stdout).unwrap();
 
     let expected = format!(
-        "in {} ",
+        "{} ",
         Color::Cyan.bold().paint("rocket-controls/src/loop/loop")
     );
     assert_eq!(expected, actual);
@@ -789,7 +780,7 @@ fn symlinked_subdirectory_git_repo_out_of_tree() -> io::Result<()> {
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
 
-    let expected = format!("in {} ", Color::Cyan.bold().paint("~/fuel-gauge"));
+    let expected = format!("{} ", Color::Cyan.bold().paint("~/fuel-gauge"));
     assert_eq!(expected, actual);
     tmp_dir.close()
 }
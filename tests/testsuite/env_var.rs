@@ -108,7 +108,7 @@ fn prefix() -> io::Result<()> {
         .use_config(toml::toml! {
             [env_var]
             variable = "TEST_VAR"
-            prefix = "_"
+            format = "with [_$env_value]($style) "
         })
         .env("TEST_VAR", TEST_VAR_VALUE)
         .output()?;
@@ -125,7 +125,7 @@ fn suffix() -> io::Result<()> {
         .use_config(toml::toml! {
             [env_var]
             variable = "TEST_VAR"
-            suffix = "_"
+            format = "with [${env_value}_]($style) "
         })
         .env("TEST_VAR", TEST_VAR_VALUE)
         .output()?;
@@ -135,6 +135,41 @@ fn suffix() -> io::Result<()> {
     Ok(())
 }
 
+#[test]
+fn redacted_variable() -> io::Result<()> {
+    let output = common::render_module("env_var")
+        .env_clear()
+        .use_config(toml::toml! {
+            [env_var]
+            variable = "TEST_VAR"
+        })
+        .env("TEST_VAR", TEST_VAR_VALUE)
+        .env("STARSHIP_REDACT", "1")
+        .output()?;
+    let expected = format!("with {} ", style().paint("***"));
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn redaction_disabled_by_env() -> io::Result<()> {
+    let output = common::render_module("env_var")
+        .env_clear()
+        .use_config(toml::toml! {
+            redact = true
+            [env_var]
+            variable = "TEST_VAR"
+        })
+        .env("TEST_VAR", TEST_VAR_VALUE)
+        .env("STARSHIP_REDACT", "0")
+        .output()?;
+    let expected = format!("with {} ", style().paint(TEST_VAR_VALUE));
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
 fn style() -> Style {
     // default style
     Color::Black.bold().dimmed()
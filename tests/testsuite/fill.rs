@@ -0,0 +1,81 @@
+use ansi_term::{ANSIStrings, Color};
+use std::io;
+use std::process::Stdio;
+
+use crate::common::{self, TestCommand};
+
+fn render(width: &str, config: toml::Value) -> io::Result<String> {
+    let output = common::render_prompt()
+        .arg(format!("--terminal-width={}", width))
+        .use_config(config)
+        .stdin(Stdio::null())
+        .output()?;
+    Ok(String::from_utf8(output.stdout).unwrap())
+}
+
+#[test]
+fn fills_the_rest_of_the_line() -> io::Result<()> {
+    let actual = render(
+        "20",
+        toml::toml! {
+            add_newline = false
+            format = "[left](red)$fill[right](blue)"
+        },
+    )?;
+    let fill = ".".repeat(11);
+    let expected = ANSIStrings(&[
+        Color::Red.paint("left"),
+        Color::Black.bold().paint(&fill),
+        Color::Blue.paint("right"),
+    ])
+    .to_string();
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn fills_each_line() -> io::Result<()> {
+    let actual = render(
+        "12",
+        toml::toml! {
+            add_newline = false
+            format = "a$fill$line_break[👋](red)$fill$fill"
+            [fill]
+            symbol = "-="
+            style = ""
+        },
+    )?;
+    // The wide character takes two columns, and the columns of the second line are shared
+    let expected = format!("a-=-=-=-=-=-\n{}-=-=--=-=-", Color::Red.paint("👋"));
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn line_wider_than_terminal() -> io::Result<()> {
+    let actual = render(
+        "3",
+        toml::toml! {
+            add_newline = false
+            format = "left$fill right"
+        },
+    )?;
+    assert_eq!("left right", actual);
+    Ok(())
+}
+
+#[test]
+fn invalid_width_falls_back() -> io::Result<()> {
+    // Without a terminal, the width falls back to 80 columns
+    let actual = render(
+        "",
+        toml::toml! {
+            add_newline = false
+            format = "$fill"
+            [fill]
+            style = ""
+        },
+    )?;
+    assert_eq!(".".repeat(80), actual);
+    Ok(())
+}
@@ -1,5 +1,6 @@
 use ansi_term::Color;
 use remove_dir_all::remove_dir_all;
+use std::fs::File;
 use std::io;
 use std::path::Path;
 use std::process::Command;
@@ -146,6 +147,72 @@ fn test_git_dir_env_variable() -> io::Result<()> {
     remove_dir_all(repo_dir)
 }
 
+#[test]
+fn test_branch_style_clean_repo() -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+
+    let output = common::render_module("git_branch")
+        .use_config(toml::toml! {
+            [git_branch]
+            format = "[$branch]($branch_style)"
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = Color::Purple.bold().paint("master").to_string();
+    assert_eq!(expected, actual);
+    remove_dir_all(repo_dir)
+}
+
+#[test]
+fn test_branch_style_dirty_repo() -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+    File::create(repo_dir.join("readme.md"))?.sync_all()?;
+
+    let output = common::render_module("git_branch")
+        .use_config(toml::toml! {
+            [git_branch]
+            format = "[$branch]($branch_style)"
+            dirty_style = "yellow"
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = Color::Yellow.paint("master").to_string();
+    assert_eq!(expected, actual);
+    remove_dir_all(repo_dir)
+}
+
+#[test]
+fn test_shortened_when_prompt_is_too_wide() -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+
+    Command::new("git")
+        .args(["checkout", "-b", "1337_hello_world"])
+        .current_dir(repo_dir.as_path())
+        .output()?;
+
+    let output = common::render_prompt()
+        .use_config(toml::toml! {
+            add_newline = false
+            format = "$git_branch"
+            [git_branch]
+            format = "$branch "
+        })
+        .arg("--terminal-width=14")
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!("1337_hel… ", actual);
+    remove_dir_all(repo_dir)
+}
+
 fn test_truncate_length(
     branch_name: &str,
     truncate_length: i64,
@@ -42,10 +42,11 @@ fn test_render_commit_hash() -> io::Result<()> {
         .output()?;
 
     let actual = String::from_utf8(output.stdout).unwrap();
-    let expected = Color::Green
+    let mut expected = Color::Green
         .bold()
-        .paint(format!("({}) ", expected_hash))
+        .paint(format!("({})", expected_hash))
         .to_string();
+    expected.push(' ');
 
     assert_eq!(expected, actual);
     remove_dir_all(repo_dir)
@@ -74,10 +75,11 @@ fn test_render_commit_hash_len_override() -> io::Result<()> {
         .output()?;
 
     let actual = String::from_utf8(output.stdout).unwrap();
-    let expected = Color::Green
+    let mut expected = Color::Green
         .bold()
-        .paint(format!("({}) ", expected_hash))
+        .paint(format!("({})", expected_hash))
         .to_string();
+    expected.push(' ');
 
     assert_eq!(expected, actual);
     remove_dir_all(repo_dir)
@@ -122,10 +124,11 @@ fn test_render_commit_hash_only_detached_on_detached() -> io::Result<()> {
 
     let actual = String::from_utf8(output.stdout).unwrap();
 
-    let expected = Color::Green
+    let mut expected = Color::Green
         .bold()
-        .paint(format!("({}) ", expected_hash))
+        .paint(format!("({})", expected_hash))
         .to_string();
+    expected.push(' ');
 
     assert_eq!(expected, actual);
     remove_dir_all(repo_dir)
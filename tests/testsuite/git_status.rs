@@ -46,7 +46,7 @@ fn shows_behind() -> io::Result<()> {
         .arg(&repo_dir)
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
-    let expected = Color::Red.bold().paint(format!("[{}] ", "⇣")).to_string();
+    let expected = format!("{} ", Color::Red.bold().paint(format!("[{}]", "⇣")));
 
     assert_eq!(expected, actual);
 
@@ -63,13 +63,13 @@ fn shows_behind_with_count() -> io::Result<()> {
     let output = common::render_module("git_status")
         .use_config(toml::toml! {
             [git_status]
-            show_sync_count = true
+            behind = "⇣$count"
         })
         .arg("--path")
         .arg(&repo_dir)
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
-    let expected = Color::Red.bold().paint(format!("[{}] ", "⇣1")).to_string();
+    let expected = format!("{} ", Color::Red.bold().paint(format!("[{}]", "⇣1")));
 
     assert_eq!(expected, actual);
 
@@ -89,7 +89,7 @@ fn shows_ahead() -> io::Result<()> {
         .arg(&repo_dir)
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
-    let expected = Color::Red.bold().paint(format!("[{}] ", "⇡")).to_string();
+    let expected = format!("{} ", Color::Red.bold().paint(format!("[{}]", "⇡")));
 
     assert_eq!(expected, actual);
 
@@ -107,13 +107,13 @@ fn shows_ahead_with_count() -> io::Result<()> {
     let output = common::render_module("git_status")
         .use_config(toml::toml! {
             [git_status]
-            show_sync_count = true
+            ahead = "⇡$count"
         })
         .arg("--path")
         .arg(&repo_dir)
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
-    let expected = Color::Red.bold().paint(format!("[{}] ", "⇡1")).to_string();
+    let expected = format!("{} ", Color::Red.bold().paint(format!("[{}]", "⇡1")));
 
     assert_eq!(expected, actual);
 
@@ -132,7 +132,7 @@ fn shows_diverged() -> io::Result<()> {
         .arg(&repo_dir)
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
-    let expected = Color::Red.bold().paint(format!("[{}] ", "⇕")).to_string();
+    let expected = format!("{} ", Color::Red.bold().paint(format!("[{}]", "⇕")));
 
     assert_eq!(expected, actual);
 
@@ -149,16 +149,13 @@ fn shows_diverged_with_count() -> io::Result<()> {
     let output = common::render_module("git_status")
         .use_config(toml::toml! {
             [git_status]
-            show_sync_count = true
+            diverged = "⇕⇡$ahead_count⇣$behind_count"
         })
         .arg("--path")
         .arg(&repo_dir)
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
-    let expected = Color::Red
-        .bold()
-        .paint(format!("[{}] ", "⇕⇡1⇣1"))
-        .to_string();
+    let expected = format!("{} ", Color::Red.bold().paint(format!("[{}]", "⇕⇡1⇣1")));
 
     assert_eq!(expected, actual);
 
@@ -177,7 +174,7 @@ fn shows_conflicted() -> io::Result<()> {
         .arg(&repo_dir)
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
-    let expected = Color::Red.bold().paint(format!("[{}] ", "=")).to_string();
+    let expected = format!("{} ", Color::Red.bold().paint(format!("[{}]", "=")));
 
     assert_eq!(expected, actual);
 
@@ -194,13 +191,13 @@ fn shows_conflicted_with_count() -> io::Result<()> {
     let output = common::render_module("git_status")
         .use_config(toml::toml! {
             [git_status]
-            conflicted_count.enabled = true
+            conflicted = "=$count"
         })
         .arg("--path")
         .arg(&repo_dir)
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
-    let expected = Color::Red.bold().paint(format!("[{}] ", "=1")).to_string();
+    let expected = format!("{} ", Color::Red.bold().paint(format!("[{}]", "=1")));
 
     assert_eq!(expected, actual);
 
@@ -219,7 +216,7 @@ fn shows_untracked_file() -> io::Result<()> {
         .arg(&repo_dir)
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
-    let expected = Color::Red.bold().paint(format!("[{}] ", "?")).to_string();
+    let expected = format!("{} ", Color::Red.bold().paint(format!("[{}]", "?")));
 
     assert_eq!(expected, actual);
 
@@ -236,13 +233,13 @@ fn shows_untracked_file_with_count() -> io::Result<()> {
     let output = common::render_module("git_status")
         .use_config(toml::toml! {
             [git_status]
-            untracked_count.enabled = true
+            untracked = "?$count"
         })
         .arg("--path")
         .arg(&repo_dir)
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
-    let expected = Color::Red.bold().paint(format!("[{}] ", "?1")).to_string();
+    let expected = format!("{} ", Color::Red.bold().paint(format!("[{}]", "?1")));
 
     assert_eq!(expected, actual);
 
@@ -293,7 +290,7 @@ fn shows_stashed() -> io::Result<()> {
         .arg(&repo_dir)
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
-    let expected = Color::Red.bold().paint(format!("[{}] ", "$")).to_string();
+    let expected = format!("{} ", Color::Red.bold().paint(format!("[{}]", "$")));
 
     assert_eq!(expected, actual);
 
@@ -317,13 +314,13 @@ fn shows_stashed_with_count() -> io::Result<()> {
     let output = common::render_module("git_status")
         .use_config(toml::toml! {
             [git_status]
-            stashed_count.enabled = true
+            stashed = "\\$$count"
         })
         .arg("--path")
         .arg(&repo_dir)
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
-    let expected = Color::Red.bold().paint(format!("[{}] ", "$1")).to_string();
+    let expected = format!("{} ", Color::Red.bold().paint(format!("[{}]", "$1")));
 
     assert_eq!(expected, actual);
     remove_dir_all(repo_dir)
@@ -341,7 +338,7 @@ fn shows_modified() -> io::Result<()> {
         .arg(&repo_dir)
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
-    let expected = Color::Red.bold().paint(format!("[{}] ", "!")).to_string();
+    let expected = format!("{} ", Color::Red.bold().paint(format!("[{}]", "!")));
 
     assert_eq!(expected, actual);
     remove_dir_all(repo_dir)
@@ -357,13 +354,13 @@ fn shows_modified_with_count() -> io::Result<()> {
     let output = common::render_module("git_status")
         .use_config(toml::toml! {
             [git_status]
-            modified_count.enabled = true
+            modified = "!$count"
         })
         .arg("--path")
         .arg(&repo_dir)
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
-    let expected = Color::Red.bold().paint(format!("[{}] ", "!1")).to_string();
+    let expected = format!("{} ", Color::Red.bold().paint(format!("[{}]", "!1")));
 
     assert_eq!(expected, actual);
 
@@ -382,7 +379,7 @@ fn shows_staged_file() -> io::Result<()> {
         .arg(&repo_dir)
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
-    let expected = Color::Red.bold().paint(format!("[{}] ", "+")).to_string();
+    let expected = format!("{} ", Color::Red.bold().paint(format!("[{}]", "+")));
 
     assert_eq!(expected, actual);
 
@@ -399,8 +396,7 @@ fn shows_staged_file_with_count() -> io::Result<()> {
     let output = common::render_module("git_status")
         .use_config(toml::toml! {
             [git_status]
-            staged_count.enabled = true
-            staged_count.style = "green"
+            staged = "+[$count](green)"
         })
         .arg("--path")
         .arg(&repo_dir)
@@ -411,9 +407,9 @@ fn shows_staged_file_with_count() -> io::Result<()> {
         ANSIStrings(&[
             Color::Red.bold().paint("[+"),
             Color::Green.paint("1"),
-            Color::Red.bold().paint("] "),
+            Color::Red.bold().paint("]"),
         ])
-    );
+    ) + " ";
 
     assert_eq!(expected, actual);
 
@@ -432,7 +428,7 @@ fn shows_renamed_file() -> io::Result<()> {
         .arg(&repo_dir)
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
-    let expected = Color::Red.bold().paint(format!("[{}] ", "»")).to_string();
+    let expected = format!("{} ", Color::Red.bold().paint(format!("[{}]", "»")));
 
     assert_eq!(expected, actual);
 
@@ -449,13 +445,13 @@ fn shows_renamed_file_with_count() -> io::Result<()> {
     let output = common::render_module("git_status")
         .use_config(toml::toml! {
             [git_status]
-            renamed_count.enabled = true
+            renamed = "»$count"
         })
         .arg("--path")
         .arg(&repo_dir)
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
-    let expected = Color::Red.bold().paint(format!("[{}] ", "»1")).to_string();
+    let expected = format!("{} ", Color::Red.bold().paint(format!("[{}]", "»1")));
 
     assert_eq!(expected, actual);
 
@@ -474,7 +470,7 @@ fn shows_deleted_file() -> io::Result<()> {
         .arg(&repo_dir)
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
-    let expected = Color::Red.bold().paint(format!("[{}] ", "✘")).to_string();
+    let expected = format!("{} ", Color::Red.bold().paint(format!("[{}]", "✘")));
 
     assert_eq!(expected, actual);
 
@@ -491,13 +487,13 @@ fn shows_deleted_file_with_count() -> io::Result<()> {
     let output = common::render_module("git_status")
         .use_config(toml::toml! {
             [git_status]
-            deleted_count.enabled = true
+            deleted = "✘$count"
         })
         .arg("--path")
         .arg(&repo_dir)
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
-    let expected = Color::Red.bold().paint(format!("[{}] ", "✘1")).to_string();
+    let expected = format!("{} ", Color::Red.bold().paint(format!("[{}]", "✘1")));
 
     assert_eq!(expected, actual);
 
@@ -515,7 +511,7 @@ fn prefix() -> io::Result<()> {
         .env_clear()
         .use_config(toml::toml! {
             [git_status]
-            prefix = "("
+            format = "\\($all_status$ahead_behind\\)"
             style = ""
         })
         .output()?;
@@ -536,7 +532,7 @@ fn suffix() -> io::Result<()> {
         .env_clear()
         .use_config(toml::toml! {
             [git_status]
-            suffix = ")"
+            format = "\\($all_status$ahead_behind\\)"
             style = ""
         })
         .output()?;
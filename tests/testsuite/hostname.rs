@@ -19,7 +19,7 @@ fn ssh_only_false() -> io::Result<()> {
         })
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
-    let expected = format!("on {} ", style().paint(hostname));
+    let expected = format!("{} in ", style().paint(hostname));
     assert_eq!(expected, actual);
     Ok(())
 }
@@ -54,7 +54,7 @@ fn ssh() -> io::Result<()> {
         .env("SSH_CONNECTION", "something")
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
-    let expected = format!("on {} ", style().paint(hostname));
+    let expected = format!("{} in ", style().paint(hostname));
     assert_eq!(expected, actual);
     Ok(())
 }
@@ -71,11 +71,11 @@ fn prefix() -> io::Result<()> {
             [hostname]
             ssh_only = false
             trim_at = ""
-            prefix = "<"
+            format = "[<$hostname]($style) in "
         })
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
-    let expected = format!("on {} ", style().paint(format!("<{}", hostname)));
+    let expected = format!("{} in ", style().paint(format!("<{}", hostname)));
     assert_eq!(actual, expected);
     Ok(())
 }
@@ -92,11 +92,11 @@ fn suffix() -> io::Result<()> {
             [hostname]
             ssh_only = false
             trim_at = ""
-            suffix = ">"
+            format = "[$hostname>]($style) in "
         })
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
-    let expected = format!("on {} ", style().paint(format!("{}>", hostname)));
+    let expected = format!("{} in ", style().paint(format!("{}>", hostname)));
     assert_eq!(actual, expected);
     Ok(())
 }
@@ -116,7 +116,7 @@ fn no_trim_at() -> io::Result<()> {
         })
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
-    let expected = format!("on {} ", style().paint(hostname));
+    let expected = format!("{} in ", style().paint(hostname));
     assert_eq!(expected, actual);
     Ok(())
 }
@@ -137,7 +137,7 @@ fn trim_at() -> io::Result<()> {
         })
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
-    let expected = format!("on {} ", style().paint(remainder));
+    let expected = format!("{} in ", style().paint(remainder));
     assert_eq!(expected, actual);
     Ok(())
 }
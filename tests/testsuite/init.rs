@@ -0,0 +1,136 @@
+use ansi_term::Color;
+use std::io;
+
+use crate::common::{self, TestCommand};
+
+#[test]
+fn init_stubs() -> io::Result<()> {
+    for shell in &["elvish", "tcsh", "xonsh"] {
+        let output = common::init_command(shell).output()?;
+        let actual = String::from_utf8(output.stdout).unwrap();
+        assert!(output.status.success());
+        assert!(actual.contains(&format!("init {} --print-full-init", shell)));
+    }
+
+    // Nushell can't evaluate the stub, so the full script is printed directly
+    let output = common::init_command("nu").output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert!(actual.contains("$env.STARSHIP_SHELL = \"nu\""));
+    assert!(!actual.contains("::STARSHIP::"));
+    Ok(())
+}
+
+#[test]
+fn full_init_scripts() -> io::Result<()> {
+    let scripts = &[
+        ("elvish", "set-env STARSHIP_SHELL \"elvish\""),
+        ("nu", "$env.STARSHIP_SHELL = \"nu\""),
+        ("tcsh", "setenv STARSHIP_SHELL tcsh;"),
+        ("xonsh", "$STARSHIP_SHELL = \"xonsh\""),
+    ];
+    for (shell, set_shell) in scripts {
+        let output = common::init_command(shell)
+            .arg("--print-full-init")
+            .output()?;
+        let actual = String::from_utf8(output.stdout).unwrap();
+        assert!(actual.contains(set_shell), "{}", shell);
+        assert!(!actual.contains("::STARSHIP::"), "{}", shell);
+    }
+    Ok(())
+}
+
+#[test]
+fn init_script_flags() -> io::Result<()> {
+    // The flags each shell can give a value to
+    let scripts: &[(&str, &[&str])] = &[
+        (
+            "elvish",
+            &[
+                "--terminal-width=",
+                "--status=",
+                "--cmd-duration=",
+                "--jobs=",
+            ],
+        ),
+        ("nu", &["--terminal-width=", "--status=", "--cmd-duration="]),
+        (
+            "tcsh",
+            &["--terminal-width=", "--status=", "--cmd-duration="],
+        ),
+        (
+            "xonsh",
+            &[
+                "--terminal-width=",
+                "--status=",
+                "--cmd-duration=",
+                "--jobs=",
+                "--keymap=",
+            ],
+        ),
+    ];
+    for (shell, flags) in scripts {
+        let output = common::init_command(shell)
+            .arg("--print-full-init")
+            .output()?;
+        let actual = String::from_utf8(output.stdout).unwrap();
+        for flag in flags.iter() {
+            assert!(actual.contains(flag), "{} doesn't pass {}", shell, flag);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn shell_escaping() -> io::Result<()> {
+    let config = toml::toml! {
+        add_newline = true
+        format = "[50%!{x}\\\\](red)$line_break"
+    };
+    let render = |shell: &str| -> io::Result<String> {
+        let output = common::render_prompt()
+            .env("STARSHIP_SHELL", shell)
+            .use_config(config.clone())
+            .output()?;
+        Ok(String::from_utf8(output.stdout).unwrap())
+    };
+    let red = |text: &str| Color::Red.paint(text).to_string();
+
+    assert_eq!(
+        render("tcsh")?,
+        "\\n%{\u{1b}[31m%}50%%\\!{x}\\\\%{\u{1b}[0m%}\\n"
+    );
+    assert_eq!(render("xonsh")?, format!("\n{}\n", red("50%!{{x}}\\")));
+    assert_eq!(render("elvish")?, format!("\n{}\n", red("50%!{x}\\")));
+    assert_eq!(render("nu")?, format!("\n{}\n", red("50%!{x}\\")));
+    Ok(())
+}
+
+#[test]
+fn shell_condition() -> io::Result<()> {
+    for shell in &["elvish", "nu", "tcsh", "xonsh"] {
+        let output = common::render_prompt()
+            .env("STARSHIP_SHELL", shell)
+            .use_config(toml::toml! {
+                add_newline = false
+                format = "default"
+                [[when]]
+                shell = ["elvish", "nu", "tcsh", "xonsh"]
+                format = "known"
+            })
+            .output()?;
+        let actual = String::from_utf8(output.stdout).unwrap();
+        assert_eq!("known", actual, "{}", shell);
+    }
+    Ok(())
+}
+
+#[test]
+fn zsh_transience_hook() -> io::Result<()> {
+    let output = common::init_command("zsh")
+        .arg("--print-full-init")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert!(actual.contains("add-zle-hook-widget zle-line-finish starship_zle-line-finish"));
+    assert!(!actual.contains("zle -N zle-line-finish"));
+    Ok(())
+}
@@ -7,16 +7,19 @@ mod configuration;
 mod directory;
 mod dotnet;
 mod env_var;
+mod fill;
 mod git_branch;
 mod git_commit;
 mod git_state;
 mod git_status;
 mod hg_branch;
 mod hostname;
+mod init;
 mod jobs;
 mod line_break;
 mod modules;
 mod nix_shell;
+mod priority;
 mod python;
 mod singularity;
 mod terraform;
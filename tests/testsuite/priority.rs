@@ -0,0 +1,120 @@
+use ansi_term::Color;
+use std::io;
+use std::process::Stdio;
+
+use crate::common::{self, TestCommand};
+
+fn render(width: Option<&str>, args: &[&str], config: toml::Value) -> io::Result<String> {
+    let mut command = common::render_prompt();
+    if let Some(width) = width {
+        command.arg(format!("--terminal-width={}", width));
+    }
+    let output = command
+        .args(args)
+        .use_config(config)
+        .stdin(Stdio::null())
+        .output()?;
+    Ok(String::from_utf8(output.stdout).unwrap())
+}
+
+fn config() -> toml::Value {
+    toml::toml! {
+        add_newline = false
+        format = "$custom.first$custom.second$custom.third"
+        [custom.first]
+        command = "echo first"
+        when = "true"
+        format = "$output "
+        [custom.second]
+        command = "echo second"
+        when = "true"
+        format = "$output "
+        priority = 10
+        [custom.third]
+        command = "echo third"
+        when = "true"
+        format = "$output "
+    }
+}
+
+#[test]
+fn fitting_prompt_is_kept() -> io::Result<()> {
+    let actual = render(Some("19"), &[], config())?;
+    assert_eq!("first second third ", actual);
+    Ok(())
+}
+
+#[test]
+fn drops_lowest_priority_module() -> io::Result<()> {
+    let actual = render(Some("18"), &[], config())?;
+    assert_eq!("first third ", actual);
+    Ok(())
+}
+
+#[test]
+fn drops_last_module_among_same_priority() -> io::Result<()> {
+    let actual = render(Some("10"), &[], config())?;
+    assert_eq!("first ", actual);
+    Ok(())
+}
+
+#[test]
+fn keeps_prompt_without_terminal_width() -> io::Result<()> {
+    let actual = render(None, &[], config())?;
+    assert_eq!("first second third ", actual);
+
+    // An empty or invalid width is the same as no width
+    for width in &["", "0", "-1", "wide"] {
+        let actual = render(Some(width), &[], config())?;
+        assert_eq!("first second third ", actual, "{:?}", width);
+    }
+    Ok(())
+}
+
+#[test]
+fn only_drops_modules_of_overflowing_line() -> io::Result<()> {
+    let actual = render(
+        Some("12"),
+        &[],
+        toml::toml! {
+            add_newline = false
+            format = "$custom.first$line_break$custom.second$custom.third"
+            [custom.first]
+            command = "echo first"
+            when = "true"
+            format = "$output "
+            priority = 0
+            [custom.second]
+            command = "echo second"
+            when = "true"
+            format = "$output "
+            [custom.third]
+            command = "echo third"
+            when = "true"
+            format = "$output "
+        },
+    )?;
+    assert_eq!("first \nsecond ", actual);
+    Ok(())
+}
+
+#[test]
+fn shortens_directory_before_dropping_it() -> io::Result<()> {
+    let config = toml::toml! {
+        add_newline = false
+        format = "$directory"
+    };
+    let path = ["--path=/some/long/network/path"];
+
+    let actual = render(Some("18"), &path, config.clone())?;
+    let expected = format!("{} ", Color::Cyan.bold().paint("long/network/path"));
+    assert_eq!(expected, actual);
+
+    let actual = render(Some("17"), &path, config.clone())?;
+    let expected = format!("{} ", Color::Cyan.bold().paint("/s/l/n/path"));
+    assert_eq!(expected, actual);
+
+    let actual = render(Some("11"), &path, config)?;
+    assert_eq!("", actual);
+    Ok(())
+}
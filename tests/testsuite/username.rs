@@ -44,7 +44,7 @@ fn current_user_not_logname() -> io::Result<()> {
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
 
-    let expected = format!("via {} ", Color::Yellow.bold().paint("cosmonaut"));
+    let expected = format!("{} in ", Color::Yellow.bold().paint("cosmonaut"));
     assert_eq!(expected, actual);
     Ok(())
 }
@@ -57,7 +57,7 @@ fn ssh_connection() -> io::Result<()> {
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
 
-    let expected = format!("via {} ", Color::Yellow.bold().paint("astronaut"));
+    let expected = format!("{} in ", Color::Yellow.bold().paint("astronaut"));
     assert_eq!(expected, actual);
     Ok(())
 }
@@ -72,7 +72,7 @@ fn show_always() -> io::Result<()> {
         .output()?;
     let actual = String::from_utf8(output.stdout).unwrap();
 
-    let expected = format!("via {} ", Color::Yellow.bold().paint("astronaut"));
+    let expected = format!("{} in ", Color::Yellow.bold().paint("astronaut"));
 
     assert_eq!(expected, actual);
     Ok(())